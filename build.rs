@@ -0,0 +1,26 @@
+const PROTOS: &[&str] = &[
+    "proto/heimdall/v1/relation_tuples.proto",
+    "proto/heimdall/v1/read_service.proto",
+    "proto/heimdall/v1/write_service.proto",
+    "proto/heimdall/v1/check_service.proto",
+    "proto/heimdall/v1/expand_service.proto",
+    "proto/heimdall/v1/network_service.proto",
+    "proto/heimdall/v1/api_key_service.proto",
+    "proto/heimdall/v1/watch_service.proto",
+    "proto/heimdall/v1/lookup_service.proto",
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = prost_build::Config::new();
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+
+    // Migrations are embedded by `sqlx::migrate!`, which cannot see new files
+    // on its own. Naming any path turns off the default of rerunning on every
+    // change, so the protos are listed too.
+    println!("cargo:rerun-if-changed=migrations");
+    println!("cargo:rerun-if-changed=proto");
+
+    tonic_prost_build::configure().compile_with_config(config, PROTOS, &["proto"])?;
+
+    Ok(())
+}
@@ -35,6 +35,7 @@ CREATE TABLE public.heimdall_relation_tuples (
       OR
       ((subject_id IS NOT NULL) AND (subject_set_namespace IS NULL) AND (subject_set_object IS NULL) AND (subject_set_relation IS NULL))
     )
+  )
 );
 
 /*
@@ -79,27 +80,6 @@ CREATE TABLE public.networks (
   updated_at TIMESTAMPTZ NOT NULL -- Last modification timestamp
 );
 
-/*
- * TABLE: schema_migrations
- *
- * PURPOSE:
- *   Manages database schema versioning and migrations.
- *
- * FEATURES:
- *   - Tracks applied schema changes
- *   - Ensures consistent database state across deployments
- *   - Supports safe incremental schema evolution
- *
- * USAGE:
- *   - Migration tracking during deployments
- *   - Version verification during application startup
- *   - Rollback support for failed migrations
- */
-CREATE TABLE public.schema_migrations (
-  version VARCHAR(48) NOT NULL, -- Migration version identifier
-  version_self INTEGER DEFAULT 0 NOT NULL -- Internal versioning counter
-);
-
 -- Primary key constraints
 ALTER TABLE ONLY public.heimdall_relation_tuples
   ADD CONSTRAINT heimdall_relation_tuples_pkey PRIMARY KEY (shard_id, nid);
@@ -107,9 +87,6 @@ ALTER TABLE ONLY public.heimdall_relation_tuples
 ALTER TABLE ONLY public.heimdall_uuid_mappings
   ADD CONSTRAINT heimdall_uuid_mappings_pkey PRIMARY KEY (id);
 
-ALTER TABLE ONLY public.networks
-  ADD CONSTRAINT networks_pkey PRIMARY KEY (id);
-
 /*
  * INDEX: heimdall_relation_tuples_full_idx
  * PURPOSE: Supports full tuple lookups with all parameters
@@ -145,20 +122,6 @@ CREATE INDEX heimdall_relation_tuples_subject_ids_idx ON public.heimdall_relatio
  */
 CREATE INDEX heimdall_relation_tuples_subject_sets_idx ON public.heimdall_relation_tuples USING btree (nid, namespace, object, relation, subject_set_namespace, subject_set_object, subject_set_relation) WHERE (subject_id IS NULL);
 
-/*
- * INDEX: schema_migration_version_idx
- * PURPOSE: Enforces unique migration versions
- * PERFORMANCE: Fast version existence checks
- */
-CREATE UNIQUE INDEX schema_migration_version_idx ON public.schema_migration USING btree (version);
-
-/*
- * INDEX: schema_migration_version_self_idx
- * PURPOSE: Supports internal versioning operations
- * PERFORMANCE: Optimizes internal version counter queries
- */
-CREATE INDEX schema_migration_version_self_idx ON public.schema_migration USING btree (version_self);
-
 /*
  * CONSTRAINT: heimdall_relation_tuples_nid_fk
  * PURPOSE: Ensures referential integrity between relation tuples and networks
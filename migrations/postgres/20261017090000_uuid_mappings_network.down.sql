@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS heimdall_uuid_mappings_nid_idx;
+
+ALTER TABLE public.heimdall_uuid_mappings
+  DROP CONSTRAINT IF EXISTS heimdall_uuid_mappings_nid_fk;
+
+ALTER TABLE public.heimdall_uuid_mappings
+  DROP COLUMN IF EXISTS nid;
@@ -0,0 +1,23 @@
+/*
+ * COLUMN: heimdall_uuid_mappings.nid
+ * PURPOSE: Ties each mapping to the network whose ID namespace produced it,
+ *          so that deleting a network also removes its mappings.
+ * NOTE: Left nullable because mappings written before this migration cannot
+ *       be attributed to a network.
+ */
+ALTER TABLE public.heimdall_uuid_mappings
+  ADD COLUMN nid UUID NULL;
+
+/*
+ * CONSTRAINT: heimdall_uuid_mappings_nid_fk
+ * PURPOSE: Ensures referential integrity between UUID mappings and networks
+ * BEHAVIOR: Cascading delete when a network is removed
+ */
+ALTER TABLE ONLY public.heimdall_uuid_mappings
+  ADD CONSTRAINT heimdall_uuid_mappings_nid_fk FOREIGN KEY (nid) REFERENCES public.networks(id) ON UPDATE RESTRICT ON DELETE CASCADE;
+
+/*
+ * INDEX: heimdall_uuid_mappings_nid_idx
+ * PURPOSE: Supports the cascading delete of a network's mappings
+ */
+CREATE INDEX heimdall_uuid_mappings_nid_idx ON public.heimdall_uuid_mappings USING btree (nid);
@@ -0,0 +1,7 @@
+ALTER TABLE public.heimdall_api_keys
+  DROP CONSTRAINT IF EXISTS heimdall_api_keys_nid_fk;
+
+DROP INDEX IF EXISTS heimdall_api_keys_nid_idx;
+DROP INDEX IF EXISTS heimdall_api_keys_key_hash_idx;
+
+DROP TABLE IF EXISTS public.heimdall_api_keys;
@@ -0,0 +1,45 @@
+/*
+ * TABLE: heimdall_api_keys
+ *
+ * PURPOSE:
+ *   Bearer API keys that authenticate callers and bind them to one network.
+ *
+ * FEATURES:
+ *   - Only a SHA-256 hash of each key is stored; the key itself is shown once
+ *   - Scopes limit a key to reads, reads and writes, or key administration
+ *   - Keys are removed together with their network
+ *
+ * USAGE:
+ *   - Authenticating HTTP and gRPC requests
+ *   - Rotating and revoking tenant credentials
+ */
+CREATE TABLE public.heimdall_api_keys (
+  id UUID NOT NULL PRIMARY KEY, -- Key identifier, safe to display
+  nid UUID NOT NULL, -- Network the key grants access to
+  name VARCHAR(200) NOT NULL DEFAULT '', -- Free-form label, e.g. the owning service
+  key_hash CHAR(64) NOT NULL, -- Hex-encoded SHA-256 of the key
+  scope VARCHAR(16) NOT NULL, -- read_only, write or admin
+  created_at TIMESTAMPTZ NOT NULL, -- Key creation timestamp
+  CONSTRAINT check_heimdall_api_keys_scope CHECK (scope IN ('read_only', 'write', 'admin'))
+);
+
+/*
+ * INDEX: heimdall_api_keys_key_hash_idx
+ * PURPOSE: Looks up the presented key on every request
+ * PERFORMANCE: Single index probe per authenticated request
+ */
+CREATE UNIQUE INDEX heimdall_api_keys_key_hash_idx ON public.heimdall_api_keys USING btree (key_hash);
+
+/*
+ * INDEX: heimdall_api_keys_nid_idx
+ * PURPOSE: Lists a network's keys and supports the cascading delete
+ */
+CREATE INDEX heimdall_api_keys_nid_idx ON public.heimdall_api_keys USING btree (nid);
+
+/*
+ * CONSTRAINT: heimdall_api_keys_nid_fk
+ * PURPOSE: Ensures referential integrity between API keys and networks
+ * BEHAVIOR: Cascading delete when a network is removed
+ */
+ALTER TABLE ONLY public.heimdall_api_keys
+  ADD CONSTRAINT heimdall_api_keys_nid_fk FOREIGN KEY (nid) REFERENCES public.networks(id) ON UPDATE RESTRICT ON DELETE CASCADE;
@@ -0,0 +1,7 @@
+ALTER TABLE public.heimdall_relation_tuple_changes
+  DROP CONSTRAINT IF EXISTS heimdall_relation_tuple_changes_nid_fk;
+
+DROP INDEX IF EXISTS heimdall_relation_tuple_changes_nid_commit_time_idx;
+DROP INDEX IF EXISTS heimdall_relation_tuple_changes_nid_id_idx;
+
+DROP TABLE IF EXISTS public.heimdall_relation_tuple_changes;
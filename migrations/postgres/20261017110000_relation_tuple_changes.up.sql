@@ -0,0 +1,51 @@
+/*
+ * TABLE: heimdall_relation_tuple_changes
+ *
+ * PURPOSE:
+ *   Append-only log of relation tuple inserts and deletes, written in the same
+ *   transaction as the change itself.
+ *
+ * FEATURES:
+ *   - Per-network ordering by id; writers of a network are serialized, so ids
+ *     follow commit order within a network
+ *   - commit_time matches the snapshot token returned by the write
+ *   - Entries are removed together with their network
+ *
+ * USAGE:
+ *   - Watch streams (gRPC and server-sent events) starting at a snapshot token
+ *   - Invalidating caches and search indexes when permissions change
+ */
+CREATE TABLE public.heimdall_relation_tuple_changes (
+  id BIGSERIAL NOT NULL PRIMARY KEY, -- Position in the log
+  nid UUID NOT NULL, -- Network the changed tuple belongs to
+  action VARCHAR(8) NOT NULL, -- insert or delete
+  namespace VARCHAR(200) NOT NULL,
+  object UUID NOT NULL,
+  relation VARCHAR(64) NOT NULL,
+  subject_id UUID NULL,
+  subject_set_namespace VARCHAR(200) NULL,
+  subject_set_object UUID NULL,
+  subject_set_relation VARCHAR(64) NULL,
+  commit_time TIMESTAMPTZ NOT NULL, -- Snapshot the change was committed at
+  CONSTRAINT check_heimdall_rtc_action CHECK (action IN ('insert', 'delete'))
+);
+
+/*
+ * INDEX: heimdall_relation_tuple_changes_nid_id_idx
+ * PURPOSE: Reads a network's changes after a position in the log
+ */
+CREATE INDEX heimdall_relation_tuple_changes_nid_id_idx ON public.heimdall_relation_tuple_changes USING btree (nid, id);
+
+/*
+ * INDEX: heimdall_relation_tuple_changes_nid_commit_time_idx
+ * PURPOSE: Finds where in the log a snapshot token starts
+ */
+CREATE INDEX heimdall_relation_tuple_changes_nid_commit_time_idx ON public.heimdall_relation_tuple_changes USING btree (nid, commit_time);
+
+/*
+ * CONSTRAINT: heimdall_relation_tuple_changes_nid_fk
+ * PURPOSE: Ensures referential integrity between changes and networks
+ * BEHAVIOR: Cascading delete when a network is removed
+ */
+ALTER TABLE ONLY public.heimdall_relation_tuple_changes
+  ADD CONSTRAINT heimdall_relation_tuple_changes_nid_fk FOREIGN KEY (nid) REFERENCES public.networks(id) ON UPDATE RESTRICT ON DELETE CASCADE;
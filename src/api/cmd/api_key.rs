@@ -0,0 +1,66 @@
+use clap::Subcommand;
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::api_key::{ApiKeyScope, generate_api_key, hash_api_key},
+    services::Services,
+    settings::Settings,
+};
+
+use super::parse_arg;
+
+#[derive(Debug, Subcommand)]
+pub enum ApiKeyCommand {
+    /// Prints a new random key and the hash to list under
+    /// `auth.root_key_hashes`. Nothing is stored.
+    Generate,
+    /// Creates a key for a network directly in the database, e.g. the first
+    /// admin key of a new tenant.
+    Create {
+        #[arg(long)]
+        network_id: Uuid,
+        /// read_only, write or admin.
+        #[arg(long, value_parser = parse_arg::<ApiKeyScope>)]
+        scope: ApiKeyScope,
+        #[arg(long, default_value = "")]
+        name: String,
+    },
+}
+
+impl ApiKeyCommand {
+    pub async fn run(self, config: Option<&str>) -> HeimdallResult<()> {
+        match self {
+            ApiKeyCommand::Generate => {
+                let secret = generate_api_key();
+                println!("key:  {secret}");
+                println!("hash: {}", hash_api_key(&secret));
+                Ok(())
+            }
+            ApiKeyCommand::Create {
+                network_id,
+                scope,
+                name,
+            } => {
+                let settings = Settings::load(config)?;
+                let pool = settings.connect().await?;
+                let services = Services::new(pool.clone(), settings.namespace_service()?);
+
+                let ctx =
+                    RequestContext::new(network_id, Uuid::new_v4().to_string(), String::new());
+                let result = services
+                    .api_key_service
+                    .create_api_key(&ctx, scope, &name)
+                    .await;
+                pool.close().await;
+
+                let created = result?;
+                println!("id:     {}", created.api_key.id);
+                println!("scope:  {}", created.api_key.scope);
+                println!("secret: {}", created.secret);
+                Ok(())
+            }
+        }
+    }
+}
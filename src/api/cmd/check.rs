@@ -0,0 +1,95 @@
+use clap::Args;
+
+use crate::{
+    api::grpc::proto::{self, CheckBatchRequest, CheckRequest},
+    error::HeimdallResult,
+    models::{check::CheckExplanation, relation_tuple::RelationTuple},
+};
+
+use super::{
+    client::{Client, ConsistencyArgs, RemoteArgs},
+    parse_arg,
+};
+
+/// Checks whether the subject of `namespace:object#relation@subject` is a
+/// member of the relation, printing `allowed` or `denied`. Several tuples are
+/// checked in one batch and printed one per line, after their result.
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    remote: RemoteArgs,
+
+    #[arg(required = true, value_parser = parse_arg::<RelationTuple<String>>)]
+    tuples: Vec<RelationTuple<String>>,
+
+    /// Maximum number of nested subject sets to follow. Capped by the server.
+    #[arg(long)]
+    max_depth: Option<u32>,
+
+    /// Also print why each check was decided the way it was: the steps that
+    /// led to the match, or every step tried without reaching the subject.
+    #[arg(long)]
+    explain: bool,
+
+    #[command(flatten)]
+    consistency: ConsistencyArgs,
+}
+
+impl CheckArgs {
+    pub async fn run(self) -> HeimdallResult<()> {
+        let client = Client::connect(&self.remote)?;
+
+        if let [tuple] = self.tuples.as_slice() {
+            let response = client
+                .check()
+                .check(client.request(CheckRequest {
+                    tuple: Some(tuple.clone().into()),
+                    max_depth: self.max_depth.unwrap_or_default(),
+                    consistency: self.consistency.consistency(),
+                    explain: self.explain,
+                }))
+                .await?
+                .into_inner();
+
+            println!("{}", decision(response.allowed));
+            return print_explanation(response.explanation);
+        }
+
+        let response = client
+            .check()
+            .check_batch(client.request(CheckBatchRequest {
+                tuples: self.tuples.iter().cloned().map(Into::into).collect(),
+                max_depth: self.max_depth.unwrap_or_default(),
+                consistency: self.consistency.consistency(),
+                explain: self.explain,
+            }))
+            .await?
+            .into_inner();
+
+        for (tuple, result) in self.tuples.iter().zip(response.results) {
+            println!("{}\t{tuple}", decision(result.allowed));
+            print_explanation(result.explanation)?;
+        }
+        Ok(())
+    }
+}
+
+fn print_explanation(explanation: Option<proto::CheckExplanation>) -> HeimdallResult<()> {
+    let Some(explanation) = explanation else {
+        return Ok(());
+    };
+    let explanation = CheckExplanation::<String>::try_from(explanation)?;
+
+    println!("  reason: {}", explanation.reason);
+    for step in explanation.path {
+        println!("  {step}");
+    }
+    for step in explanation.tried {
+        println!("  tried {step}");
+    }
+    Ok(())
+}
+
+fn decision(allowed: bool) -> &'static str {
+    if allowed { "allowed" } else { "denied" }
+}
@@ -0,0 +1,141 @@
+use clap::Args;
+use tonic::{
+    Request,
+    metadata::MetadataValue,
+    transport::{Channel, Endpoint},
+};
+use uuid::Uuid;
+
+use crate::{
+    api::grpc::proto::{
+        self, check_service_client::CheckServiceClient, consistency::Requirement,
+        expand_service_client::ExpandServiceClient, lookup_service_client::LookupServiceClient,
+        read_service_client::ReadServiceClient, watch_service_client::WatchServiceClient,
+        write_service_client::WriteServiceClient,
+    },
+    context::NETWORK_ID_HEADER,
+    error::HeimdallResult,
+    models::snapshot::SnapshotToken,
+};
+
+use super::parse_arg;
+
+/// Where to reach a running server.
+#[derive(Debug, Args)]
+pub struct RemoteArgs {
+    /// gRPC endpoint of the read API.
+    #[arg(
+        long,
+        env = "HEIMDALL_READ_REMOTE",
+        default_value = "http://127.0.0.1:4467"
+    )]
+    pub read_remote: String,
+
+    /// gRPC endpoint of the write API.
+    #[arg(
+        long,
+        env = "HEIMDALL_WRITE_REMOTE",
+        default_value = "http://127.0.0.1:4469"
+    )]
+    pub write_remote: String,
+
+    /// Network the relation tuples belong to. May be left out when the API
+    /// key belongs to a network.
+    #[arg(long, env = "HEIMDALL_NETWORK_ID")]
+    pub network_id: Option<Uuid>,
+
+    /// Bearer API key sent with every request.
+    #[arg(long, env = "HEIMDALL_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
+}
+
+/// Snapshot a read is evaluated at, given a token printed by a write. Reads
+/// the latest snapshot by default.
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+pub struct ConsistencyArgs {
+    /// Sees at least every write up to the snapshot token.
+    #[arg(long, value_parser = parse_arg::<SnapshotToken>)]
+    pub at_least_as_fresh: Option<SnapshotToken>,
+
+    /// Sees only writes committed at or before the snapshot token.
+    #[arg(long, value_parser = parse_arg::<SnapshotToken>)]
+    pub at_exact_snapshot: Option<SnapshotToken>,
+}
+
+impl ConsistencyArgs {
+    pub fn consistency(&self) -> Option<proto::Consistency> {
+        let requirement = match (self.at_least_as_fresh, self.at_exact_snapshot) {
+            (Some(token), _) => Requirement::AtLeastAsFresh(token.to_string()),
+            (None, Some(token)) => Requirement::AtExactSnapshot(token.to_string()),
+            (None, None) => return None,
+        };
+        Some(proto::Consistency {
+            requirement: Some(requirement),
+        })
+    }
+}
+
+/// gRPC clients that tag every request with the network id and API key.
+pub struct Client {
+    read_channel: Channel,
+    write_channel: Channel,
+    network_id: Option<Uuid>,
+    api_key: Option<String>,
+}
+
+impl Client {
+    /// Connects lazily, so a command only needs the API it talks to.
+    pub fn connect(args: &RemoteArgs) -> HeimdallResult<Self> {
+        Ok(Self {
+            read_channel: Endpoint::from_shared(args.read_remote.clone())?.connect_lazy(),
+            write_channel: Endpoint::from_shared(args.write_remote.clone())?.connect_lazy(),
+            network_id: args.network_id,
+            api_key: args.api_key.clone(),
+        })
+    }
+
+    pub fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        // Both values are ASCII, a UUID always and a key unless mistyped, in
+        // which case the server rejects the request as unauthenticated.
+        if let Some(value) = self
+            .network_id
+            .and_then(|id| MetadataValue::try_from(id.to_string()).ok())
+        {
+            request.metadata_mut().insert(NETWORK_ID_HEADER, value);
+        }
+        if let Some(value) = self
+            .api_key
+            .as_ref()
+            .and_then(|key| MetadataValue::try_from(format!("Bearer {key}")).ok())
+        {
+            request.metadata_mut().insert("authorization", value);
+        }
+        request
+    }
+
+    pub fn read(&self) -> ReadServiceClient<Channel> {
+        ReadServiceClient::new(self.read_channel.clone())
+    }
+
+    pub fn write(&self) -> WriteServiceClient<Channel> {
+        WriteServiceClient::new(self.write_channel.clone())
+    }
+
+    pub fn check(&self) -> CheckServiceClient<Channel> {
+        CheckServiceClient::new(self.read_channel.clone())
+    }
+
+    pub fn expand(&self) -> ExpandServiceClient<Channel> {
+        ExpandServiceClient::new(self.read_channel.clone())
+    }
+
+    pub fn lookup(&self) -> LookupServiceClient<Channel> {
+        LookupServiceClient::new(self.read_channel.clone())
+    }
+
+    pub fn watch(&self) -> WatchServiceClient<Channel> {
+        WatchServiceClient::new(self.read_channel.clone())
+    }
+}
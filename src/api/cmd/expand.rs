@@ -0,0 +1,64 @@
+use clap::Args;
+
+use crate::{
+    api::grpc::proto::ExpandRequest,
+    error::HeimdallResult,
+    models::{expand::Tree, relation_tuple::SubjectSet},
+};
+
+use super::{
+    client::{Client, ConsistencyArgs, RemoteArgs},
+    parse_arg,
+};
+
+/// Prints the subject tree of `namespace:object#relation`.
+#[derive(Debug, Args)]
+pub struct ExpandArgs {
+    #[command(flatten)]
+    remote: RemoteArgs,
+
+    #[arg(value_parser = parse_arg::<SubjectSet<String>>)]
+    subject_set: SubjectSet<String>,
+
+    /// Maximum number of nested subject sets to follow. Capped by the server.
+    #[arg(long)]
+    max_depth: Option<u32>,
+
+    #[command(flatten)]
+    consistency: ConsistencyArgs,
+}
+
+impl ExpandArgs {
+    pub async fn run(self) -> HeimdallResult<()> {
+        let client = Client::connect(&self.remote)?;
+
+        let response = client
+            .expand()
+            .expand(client.request(ExpandRequest {
+                subject_set: Some(self.subject_set.into()),
+                max_depth: self.max_depth.unwrap_or_default(),
+                consistency: self.consistency.consistency(),
+            }))
+            .await?
+            .into_inner();
+
+        match response.tree {
+            Some(tree) => print_tree(&Tree::<String>::try_from(tree)?, 0),
+            None => println!("empty"),
+        }
+        Ok(())
+    }
+}
+
+fn print_tree(tree: &Tree<String>, depth: usize) {
+    println!(
+        "{:indent$}{} {}",
+        "",
+        tree.node_type,
+        tree.subject,
+        indent = depth * 2
+    );
+    for child in &tree.children {
+        print_tree(child, depth + 1);
+    }
+}
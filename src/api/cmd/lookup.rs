@@ -0,0 +1,123 @@
+use clap::Args;
+use uuid::Uuid;
+
+use crate::{
+    api::grpc::proto::{ListObjectsRequest, ListSubjectsRequest},
+    error::HeimdallResult,
+    models::relation_tuple::{Subject, SubjectSet},
+};
+
+use super::{
+    client::{Client, ConsistencyArgs, RemoteArgs},
+    parse_arg,
+};
+
+/// Lists the objects of a namespace whose relation contains the subject, one
+/// per line.
+#[derive(Debug, Args)]
+pub struct ListObjectsArgs {
+    #[command(flatten)]
+    remote: RemoteArgs,
+
+    namespace: String,
+
+    relation: String,
+
+    /// A subject ID or `namespace:object#relation`.
+    #[arg(value_parser = parse_arg::<Subject<String>>)]
+    subject: Subject<String>,
+
+    #[arg(long)]
+    page_size: Option<i32>,
+
+    #[arg(long)]
+    page_token: Option<String>,
+
+    /// Maximum number of nested subject sets to follow. Capped by the server.
+    #[arg(long)]
+    max_depth: Option<u32>,
+
+    #[command(flatten)]
+    consistency: ConsistencyArgs,
+}
+
+impl ListObjectsArgs {
+    pub async fn run(self) -> HeimdallResult<()> {
+        let client = Client::connect(&self.remote)?;
+
+        let response = client
+            .lookup()
+            .list_objects(client.request(ListObjectsRequest {
+                namespace: self.namespace,
+                relation: self.relation,
+                subject: Some(self.subject.into()),
+                page_size: self.page_size.unwrap_or_default(),
+                page_token: self.page_token.unwrap_or_default(),
+                max_depth: self.max_depth.unwrap_or_default(),
+                consistency: self.consistency.consistency(),
+            }))
+            .await?
+            .into_inner();
+
+        for object in response.objects {
+            println!("{object}");
+        }
+        print_next_page_token(&response.next_page_token);
+        Ok(())
+    }
+}
+
+/// Lists the distinct subject IDs in `namespace:object#relation`, with every
+/// nested subject set resolved, one per line.
+#[derive(Debug, Args)]
+pub struct ListSubjectsArgs {
+    #[command(flatten)]
+    remote: RemoteArgs,
+
+    #[arg(value_parser = parse_arg::<SubjectSet<String>>)]
+    subject_set: SubjectSet<String>,
+
+    #[arg(long)]
+    page_size: Option<i32>,
+
+    #[arg(long)]
+    page_token: Option<String>,
+
+    /// Maximum number of nested subject sets to follow. Capped by the server.
+    #[arg(long)]
+    max_depth: Option<u32>,
+
+    #[command(flatten)]
+    consistency: ConsistencyArgs,
+}
+
+impl ListSubjectsArgs {
+    pub async fn run(self) -> HeimdallResult<()> {
+        let client = Client::connect(&self.remote)?;
+
+        let response = client
+            .lookup()
+            .list_subjects(client.request(ListSubjectsRequest {
+                subject_set: Some(self.subject_set.into()),
+                page_size: self.page_size.unwrap_or_default(),
+                page_token: self.page_token.unwrap_or_default(),
+                max_depth: self.max_depth.unwrap_or_default(),
+                consistency: self.consistency.consistency(),
+            }))
+            .await?
+            .into_inner();
+
+        for subject_id in response.subject_ids {
+            println!("{subject_id}");
+        }
+        print_next_page_token(&response.next_page_token);
+        Ok(())
+    }
+}
+
+fn print_next_page_token(token: &str) {
+    // The last page carries an empty or nil token.
+    if !token.is_empty() && token != Uuid::nil().to_string() {
+        eprintln!("next page token: {token}");
+    }
+}
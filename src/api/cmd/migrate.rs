@@ -0,0 +1,47 @@
+use clap::Subcommand;
+
+use crate::{error::HeimdallResult, persistance::migrations::MigrationRunner, settings::Settings};
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Applies every pending migration.
+    Up,
+    /// Reverts the most recently applied migrations.
+    Down {
+        /// Number of migrations to revert.
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// Lists the migrations known to this binary and whether they are applied.
+    Status,
+}
+
+impl MigrateCommand {
+    pub async fn run(self, settings: Settings) -> HeimdallResult<()> {
+        let pool = settings.connect().await?;
+        let runner = MigrationRunner::new(pool.clone());
+
+        let result = match self {
+            MigrateCommand::Up => runner.up().await.map(|()| println!("migrations applied")),
+            MigrateCommand::Down { steps } => runner.down(steps).await.map(|reverted| {
+                for version in reverted {
+                    println!("reverted {version}");
+                }
+            }),
+            MigrateCommand::Status => runner.status().await.map(|statuses| {
+                println!("{:<16}{:<10}DESCRIPTION", "VERSION", "STATE");
+                for status in statuses {
+                    println!(
+                        "{:<16}{:<10}{}",
+                        status.version,
+                        status.state.to_string(),
+                        status.description
+                    );
+                }
+            }),
+        };
+
+        pool.close().await;
+        result
+    }
+}
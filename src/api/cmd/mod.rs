@@ -1 +1,98 @@
+mod api_key;
+mod check;
+mod client;
+mod expand;
+mod lookup;
+mod migrate;
+mod relation_tuple;
+mod serve;
 
+use std::{process::ExitCode, str::FromStr};
+
+use clap::{Parser, Subcommand};
+
+use crate::{error::HeimdallError, settings::Settings};
+
+use self::{
+    api_key::ApiKeyCommand,
+    check::CheckArgs,
+    expand::ExpandArgs,
+    lookup::{ListObjectsArgs, ListSubjectsArgs},
+    migrate::MigrateCommand,
+    relation_tuple::RelationTupleCommand,
+    serve::ServeArgs,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "heimdall",
+    version,
+    about = "Zanzibar-style authorization server"
+)]
+struct Cli {
+    /// Path to the config file. Defaults to `config/heimdall.yml` when present.
+    #[arg(short, long, global = true, env = "HEIMDALL_CONFIG")]
+    config: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Runs the HTTP and gRPC servers until SIGINT or SIGTERM. Refuses to
+    /// start while migrations are pending.
+    Serve(ServeArgs),
+    /// Applies, reverts or lists the embedded database migrations.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Generates root keys and creates network API keys.
+    #[command(subcommand)]
+    ApiKey(ApiKeyCommand),
+    /// Creates, lists, deletes and parses relation tuples on a running server.
+    #[command(subcommand)]
+    RelationTuple(RelationTupleCommand),
+    Check(CheckArgs),
+    Expand(ExpandArgs),
+    ListObjects(ListObjectsArgs),
+    ListSubjects(ListSubjectsArgs),
+}
+
+/// Lets clap parse arguments with the models' `FromStr` implementations.
+fn parse_arg<T>(value: &str) -> Result<T, String>
+where
+    T: FromStr<Err = HeimdallError>,
+{
+    value
+        .parse()
+        .map_err(|e: HeimdallError| e.to_string().trim_end().to_string())
+}
+
+pub async fn run() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Serve(args) => match Settings::load(cli.config.as_deref()) {
+            Ok(settings) => args.run(settings).await,
+            Err(e) => Err(e),
+        },
+        Command::Migrate(command) => match Settings::load(cli.config.as_deref()) {
+            Ok(settings) => command.run(settings).await,
+            Err(e) => Err(e),
+        },
+        Command::ApiKey(command) => command.run(cli.config.as_deref()).await,
+        Command::RelationTuple(command) => command.run().await,
+        Command::Check(args) => args.run().await,
+        Command::Expand(args) => args.run().await,
+        Command::ListObjects(args) => args.run().await,
+        Command::ListSubjects(args) => args.run().await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e.to_string().trim_end());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use clap::Subcommand;
+use uuid::Uuid;
+
+use crate::{
+    api::grpc::proto::{
+        ListRelationTuplesRequest, RelationQuery, RelationTupleDelta,
+        TransactRelationTuplesRequest, WatchRequest, relation_tuple_delta::Action, watch_response,
+    },
+    error::{HeimdallError, HeimdallResult},
+    models::{
+        relation_tuple::{RelationTuple, Subject},
+        snapshot::SnapshotToken,
+    },
+};
+
+use super::{
+    client::{Client, ConsistencyArgs, RemoteArgs},
+    parse_arg,
+};
+
+#[derive(Debug, Subcommand)]
+pub enum RelationTupleCommand {
+    /// Writes relation tuples given as `namespace:object#relation@subject`.
+    Create {
+        #[command(flatten)]
+        remote: RemoteArgs,
+        #[arg(required = true, value_parser = parse_arg::<RelationTuple<String>>)]
+        tuples: Vec<RelationTuple<String>>,
+    },
+    /// Lists relation tuples matching the filters, one per line.
+    Get {
+        #[command(flatten)]
+        remote: RemoteArgs,
+        #[arg(long)]
+        namespace: Option<String>,
+        #[arg(long)]
+        object: Option<String>,
+        #[arg(long)]
+        relation: Option<String>,
+        /// A subject ID or `namespace:object#relation`.
+        #[arg(long, value_parser = parse_arg::<Subject<String>>)]
+        subject: Option<Subject<String>>,
+        #[arg(long)]
+        page_size: Option<i32>,
+        #[arg(long)]
+        page_token: Option<String>,
+        #[command(flatten)]
+        consistency: ConsistencyArgs,
+    },
+    /// Deletes relation tuples given as `namespace:object#relation@subject`.
+    Delete {
+        #[command(flatten)]
+        remote: RemoteArgs,
+        #[arg(required = true, value_parser = parse_arg::<RelationTuple<String>>)]
+        tuples: Vec<RelationTuple<String>>,
+    },
+    /// Prints inserts and deletes as they are committed, as `+ tuple` and
+    /// `- tuple` lines, until interrupted.
+    Watch {
+        #[command(flatten)]
+        remote: RemoteArgs,
+        /// Starts after this snapshot token instead of with the next change.
+        #[arg(long, value_parser = parse_arg::<SnapshotToken>)]
+        snaptoken: Option<SnapshotToken>,
+    },
+    /// Prints relation tuples as the JSON accepted by the HTTP API.
+    Parse {
+        #[arg(required = true, value_parser = parse_arg::<RelationTuple<String>>)]
+        tuples: Vec<RelationTuple<String>>,
+    },
+}
+
+impl RelationTupleCommand {
+    pub async fn run(self) -> HeimdallResult<()> {
+        match self {
+            RelationTupleCommand::Create { remote, tuples } => {
+                transact(&remote, tuples, Action::Insert).await
+            }
+            RelationTupleCommand::Delete { remote, tuples } => {
+                transact(&remote, tuples, Action::Delete).await
+            }
+            RelationTupleCommand::Get {
+                remote,
+                namespace,
+                object,
+                relation,
+                subject,
+                page_size,
+                page_token,
+                consistency,
+            } => {
+                let query = RelationQuery {
+                    namespace,
+                    object,
+                    relation,
+                    subject: subject.map(Into::into),
+                };
+                get(&remote, query, page_size, page_token, &consistency).await
+            }
+            RelationTupleCommand::Watch { remote, snaptoken } => watch(&remote, snaptoken).await,
+            RelationTupleCommand::Parse { tuples } => {
+                for tuple in tuples {
+                    let json = serde_json::to_string_pretty(&tuple)
+                        .map_err(|_| HeimdallError::MalformedInput)?;
+                    println!("{json}");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn transact(
+    remote: &RemoteArgs,
+    tuples: Vec<RelationTuple<String>>,
+    action: Action,
+) -> HeimdallResult<()> {
+    let client = Client::connect(remote)?;
+
+    let relation_tuple_deltas = tuples
+        .iter()
+        .map(|tuple| RelationTupleDelta {
+            action: action.into(),
+            relation_tuple: Some(tuple.clone().into()),
+        })
+        .collect();
+
+    let response = client
+        .write()
+        .transact_relation_tuples(client.request(TransactRelationTuplesRequest {
+            relation_tuple_deltas,
+        }))
+        .await?
+        .into_inner();
+
+    for tuple in tuples {
+        println!("{tuple}");
+    }
+    eprintln!("snapshot token: {}", response.snaptoken);
+    Ok(())
+}
+
+async fn get(
+    remote: &RemoteArgs,
+    query: RelationQuery,
+    page_size: Option<i32>,
+    page_token: Option<String>,
+    consistency: &ConsistencyArgs,
+) -> HeimdallResult<()> {
+    let client = Client::connect(remote)?;
+
+    let response = client
+        .read()
+        .list_relation_tuples(client.request(ListRelationTuplesRequest {
+            relation_query: Some(query),
+            page_size: page_size.unwrap_or_default(),
+            page_token: page_token.unwrap_or_default(),
+            consistency: consistency.consistency(),
+        }))
+        .await?
+        .into_inner();
+
+    for tuple in response.relation_tuples {
+        let tuple: RelationTuple<String> = tuple.try_into()?;
+        println!("{tuple}");
+    }
+
+    // The last page carries an empty or nil token.
+    if !response.next_page_token.is_empty() && response.next_page_token != Uuid::nil().to_string() {
+        eprintln!("next page token: {}", response.next_page_token);
+    }
+    Ok(())
+}
+
+async fn watch(remote: &RemoteArgs, snaptoken: Option<SnapshotToken>) -> HeimdallResult<()> {
+    let client = Client::connect(remote)?;
+
+    let mut changes = client
+        .watch()
+        .watch(client.request(WatchRequest {
+            snaptoken: snaptoken.map(|token| token.to_string()).unwrap_or_default(),
+        }))
+        .await?
+        .into_inner();
+
+    while let Some(change) = changes.message().await? {
+        let sign = match change.action() {
+            watch_response::Action::Insert => '+',
+            watch_response::Action::Delete => '-',
+            watch_response::Action::Unspecified => '?',
+        };
+        let tuple: RelationTuple<String> = change
+            .relation_tuple
+            .ok_or(HeimdallError::MalformedInput)?
+            .try_into()?;
+        println!("{sign} {tuple}");
+    }
+    Ok(())
+}
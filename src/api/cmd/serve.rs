@@ -0,0 +1,150 @@
+use std::{sync::Arc, time::Duration};
+
+use clap::Args;
+use tokio::{signal, sync::watch};
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+use crate::{
+    api::{ApiKind, ApiServer, ApiState},
+    error::HeimdallResult,
+    middlewares::{Authenticator, NetworkResolver, RequestContextLayer},
+    persistance::migrations::MigrationRunner,
+    services::Services,
+    settings::{LogFormat, LogSettings, Settings},
+};
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// APIs to serve, e.g. `--api read` to run the read API in its own
+    /// process. Serves both by default.
+    #[arg(
+        long = "api",
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [ApiKind::Read, ApiKind::Write]
+    )]
+    apis: Vec<ApiKind>,
+}
+
+impl ServeArgs {
+    pub async fn run(self, settings: Settings) -> HeimdallResult<()> {
+        init_tracing(&settings.log);
+
+        let namespace_service = settings.namespace_service()?;
+        let pool = settings.connect().await?;
+        MigrationRunner::new(pool.clone())
+            .ensure_up_to_date()
+            .await?;
+
+        let services = Services::new(pool.clone(), namespace_service);
+        let state = ApiState::new(
+            services,
+            settings.limit.max_depth,
+            settings.limit.max_batch_size,
+            settings.watch.poll_interval(),
+        );
+
+        if !settings.auth.enabled {
+            warn!("authentication is disabled, every caller can act on every network");
+        }
+        let authenticator = Arc::new(Authenticator::new(
+            settings.auth.enabled,
+            settings.auth.root_key_hashes.clone(),
+            state.services.api_key_service.clone(),
+        ));
+
+        let context_layer =
+            RequestContextLayer::new(NetworkResolver::new(settings.network.hosts.clone()));
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(());
+
+        let serve_api = |kind: ApiKind, listen| {
+            let server = self.apis.contains(&kind).then(|| {
+                ApiServer::new(
+                    kind,
+                    state.clone(),
+                    authenticator.clone(),
+                    context_layer.clone(),
+                )
+            });
+            let shutdown = shutdown_rx.clone();
+            async move {
+                match server {
+                    Some(server) => server.serve(listen, shutdown).await,
+                    None => Ok(()),
+                }
+            }
+        };
+
+        let servers = async {
+            tokio::try_join!(
+                serve_api(ApiKind::Read, &settings.serve.read),
+                serve_api(ApiKind::Write, &settings.serve.write),
+            )
+        };
+        tokio::pin!(servers);
+
+        // Any server failing stops the others through try_join. Otherwise
+        // they all drain in-flight requests once a signal arrives.
+        let result = tokio::select! {
+            result = &mut servers => result.map(|_| ()),
+            _ = shutdown_signal() => {
+                info!("shutting down");
+                let _ = shutdown_tx.send(());
+
+                let timeout = Duration::from_secs(settings.serve.shutdown_timeout_secs);
+                match tokio::time::timeout(timeout, &mut servers).await {
+                    Ok(result) => result.map(|_| ()),
+                    Err(_) => {
+                        warn!("in-flight requests did not finish before the shutdown timeout");
+                        Ok(())
+                    }
+                }
+            }
+        };
+
+        pool.close().await;
+        result
+    }
+}
+
+fn init_tracing(log: &LogSettings) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&log.level));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+
+    match log.format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = signal::ctrl_c().await {
+            warn!(error = %e, "unable to listen for ctrl-c");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!(error = %e, "unable to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
@@ -0,0 +1,81 @@
+use tonic::{Request, Response, Status};
+
+use crate::{api::ApiState, models::api_key::ApiKeyScope};
+
+use super::{
+    context::request_context,
+    convert::parse_uuid,
+    proto::{
+        CreateApiKeyRequest, CreateApiKeyResponse, DeleteApiKeyRequest, DeleteApiKeyResponse,
+        ListApiKeysRequest, ListApiKeysResponse, api_key_service_server::ApiKeyService,
+    },
+};
+
+pub struct ApiKeyHandler {
+    state: ApiState,
+}
+
+impl ApiKeyHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl ApiKeyService for ApiKeyHandler {
+    async fn create_api_key(
+        &self,
+        request: Request<CreateApiKeyRequest>,
+    ) -> Result<Response<CreateApiKeyResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+
+        let scope: ApiKeyScope = request.scope().try_into()?;
+
+        let created = self
+            .state
+            .services
+            .api_key_service
+            .create_api_key(&ctx, scope, &request.name)
+            .await?;
+
+        Ok(Response::new(CreateApiKeyResponse {
+            api_key: Some(created.api_key.into()),
+            secret: created.secret,
+        }))
+    }
+
+    async fn list_api_keys(
+        &self,
+        request: Request<ListApiKeysRequest>,
+    ) -> Result<Response<ListApiKeysResponse>, Status> {
+        let ctx = request_context(&request)?;
+
+        let api_keys = self
+            .state
+            .services
+            .api_key_service
+            .list_api_keys(&ctx)
+            .await?;
+
+        Ok(Response::new(ListApiKeysResponse {
+            api_keys: api_keys.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn delete_api_key(
+        &self,
+        request: Request<DeleteApiKeyRequest>,
+    ) -> Result<Response<DeleteApiKeyResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let id = parse_uuid(&request.into_inner().id)?;
+
+        self.state
+            .services
+            .api_key_service
+            .delete_api_key(&ctx, id)
+            .await?;
+
+        Ok(Response::new(DeleteApiKeyResponse {}))
+    }
+}
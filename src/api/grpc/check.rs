@@ -0,0 +1,126 @@
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::{ApiState, mapper::IdMapper},
+    error::HeimdallError,
+    models::{check::CheckResult, relation_tuple::RelationTuple},
+};
+
+use super::{
+    context::request_context,
+    convert::consistency,
+    proto::{
+        CheckBatchRequest, CheckBatchResponse, CheckRequest, CheckResponse,
+        check_service_server::CheckService,
+    },
+};
+
+pub struct CheckHandler {
+    state: ApiState,
+}
+
+impl CheckHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl CheckService for CheckHandler {
+    async fn check(
+        &self,
+        request: Request<CheckRequest>,
+    ) -> Result<Response<CheckResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+        let ctx = self
+            .state
+            .read_context(ctx, consistency(request.consistency)?)
+            .await?;
+
+        let tuple: RelationTuple<String> = request
+            .tuple
+            .ok_or(HeimdallError::MalformedInput)?
+            .try_into()?;
+        let mapped = self
+            .state
+            .id_mapper
+            .to_uuids_readonly(&ctx, tuple.clone())
+            .await?;
+        let max_depth = (request.max_depth > 0).then_some(request.max_depth);
+
+        let result = self
+            .state
+            .check_engine
+            .check(&ctx, &mapped, max_depth, request.explain)
+            .await?;
+        let explanation = if request.explain {
+            let given = IdMapper::given_ids(&tuple, &mapped);
+            let explanation = result.explanation();
+            Some(
+                self.state
+                    .id_mapper
+                    .to_strings_given(&ctx, explanation, given)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Response::new(CheckResponse {
+            allowed: result.is_allowed(),
+            explanation: explanation.map(Into::into),
+        }))
+    }
+
+    async fn check_batch(
+        &self,
+        request: Request<CheckBatchRequest>,
+    ) -> Result<Response<CheckBatchResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+        let ctx = self
+            .state
+            .read_context(ctx, consistency(request.consistency)?)
+            .await?;
+
+        let tuples = request
+            .tuples
+            .into_iter()
+            .map(RelationTuple::<String>::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mapped = self
+            .state
+            .id_mapper
+            .to_uuids_readonly(&ctx, tuples.clone())
+            .await?;
+        let max_depth = (request.max_depth > 0).then_some(request.max_depth);
+
+        let results = self
+            .state
+            .check_engine
+            .check_batch(&ctx, &mapped, max_depth, request.explain)
+            .await?;
+        let explanations = if request.explain {
+            let given = IdMapper::given_ids(&tuples, &mapped);
+            let explanations: Vec<_> = results.iter().map(CheckResult::explanation).collect();
+            self.state
+                .id_mapper
+                .to_strings_given(&ctx, explanations, given)
+                .await?
+        } else {
+            Vec::new()
+        };
+        let mut explanations = explanations.into_iter();
+
+        Ok(Response::new(CheckBatchResponse {
+            results: results
+                .iter()
+                .map(|result| CheckResponse {
+                    allowed: result.is_allowed(),
+                    explanation: explanations.next().map(Into::into),
+                })
+                .collect(),
+        }))
+    }
+}
@@ -0,0 +1,13 @@
+use tonic::{Request, Status};
+
+use crate::{context::RequestContext, error::HeimdallError, middlewares::RequestMetadata};
+
+/// Reads the metadata attached by `RequestContextLayer`.
+pub fn request_context<T>(request: &Request<T>) -> Result<RequestContext, Status> {
+    let ctx = request
+        .extensions()
+        .get::<RequestMetadata>()
+        .ok_or(HeimdallError::NetworkMissing)?
+        .context()?;
+    Ok(ctx)
+}
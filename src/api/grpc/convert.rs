@@ -0,0 +1,363 @@
+use uuid::Uuid;
+
+use crate::{
+    error::{HeimdallError, HeimdallResult},
+    models::{
+        api_key::{ApiKey, ApiKeyScope},
+        change::{ChangeAction, RelationTupleChange},
+        check::{CheckExplanation, CheckReason},
+        expand::{Tree, TreeNodeType},
+        network::Network,
+        query::{TokenPagination, relation_tuple::RelationTupleQuery},
+        relation_tuple::{RelationTuple, Subject, SubjectID, SubjectSet},
+        snapshot::Consistency,
+        traversal::{Traversal, TraversalResult},
+    },
+};
+
+use super::proto;
+
+pub fn parse_uuid(value: &str) -> HeimdallResult<Uuid> {
+    Uuid::parse_str(value).map_err(|_| HeimdallError::MalformedInput)
+}
+
+impl TryFrom<proto::SubjectSet> for SubjectSet<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::SubjectSet) -> Result<Self, Self::Error> {
+        Ok(SubjectSet::new(
+            value.namespace,
+            value.object,
+            value.relation,
+        ))
+    }
+}
+
+impl From<SubjectSet<String>> for proto::SubjectSet {
+    fn from(value: SubjectSet<String>) -> Self {
+        Self {
+            namespace: value.namespace,
+            object: value.object,
+            relation: value.relation,
+        }
+    }
+}
+
+impl TryFrom<proto::Subject> for Subject<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::Subject) -> Result<Self, Self::Error> {
+        match value.r#ref {
+            Some(proto::subject::Ref::Id(id)) => Ok(Subject::Direct(SubjectID::new(id))),
+            Some(proto::subject::Ref::Set(set)) => Ok(Subject::Set(set.try_into()?)),
+            None => Err(HeimdallError::NilSubjectError),
+        }
+    }
+}
+
+impl From<Subject<String>> for proto::Subject {
+    fn from(value: Subject<String>) -> Self {
+        let r#ref = match value {
+            Subject::Direct(SubjectID { id }) => proto::subject::Ref::Id(id),
+            Subject::Set(set) => proto::subject::Ref::Set(set.into()),
+        };
+        Self { r#ref: Some(r#ref) }
+    }
+}
+
+impl TryFrom<proto::RelationTuple> for RelationTuple<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::RelationTuple) -> Result<Self, Self::Error> {
+        Ok(RelationTuple {
+            namespace: value.namespace,
+            object: value.object,
+            relation: value.relation,
+            subject: value
+                .subject
+                .ok_or(HeimdallError::NilSubjectError)?
+                .try_into()?,
+        })
+    }
+}
+
+impl From<RelationTuple<String>> for proto::RelationTuple {
+    fn from(value: RelationTuple<String>) -> Self {
+        Self {
+            namespace: value.namespace,
+            object: value.object,
+            relation: value.relation,
+            subject: Some(value.subject.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::RelationQuery> for RelationTupleQuery<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::RelationQuery) -> Result<Self, Self::Error> {
+        Ok(RelationTupleQuery {
+            namespace: value.namespace,
+            object: value.object,
+            relation: value.relation,
+            subject: value.subject.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::Consistency> for Consistency {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::Consistency) -> Result<Self, Self::Error> {
+        match value.requirement {
+            Some(proto::consistency::Requirement::AtLeastAsFresh(token)) => {
+                Ok(Consistency::AtLeastAsFresh(token.parse()?))
+            }
+            Some(proto::consistency::Requirement::AtExactSnapshot(token)) => {
+                Ok(Consistency::AtExactSnapshot(token.parse()?))
+            }
+            None => Ok(Consistency::Latest),
+        }
+    }
+}
+
+/// An unset consistency reads the latest snapshot.
+pub fn consistency(value: Option<proto::Consistency>) -> HeimdallResult<Consistency> {
+    value.map_or(Ok(Consistency::Latest), TryInto::try_into)
+}
+
+/// An empty page token starts at the first page and a zero page size, the
+/// protobuf default, picks the server's. Other sizes are validated like over
+/// HTTP.
+pub fn pagination(page_token: &str, page_size: i32) -> HeimdallResult<TokenPagination> {
+    Ok(TokenPagination {
+        last_id: match page_token {
+            "" => None,
+            token => TokenPagination::decode_page_token(token)?,
+        },
+        page_size: TokenPagination::checked_page_size((page_size != 0).then_some(page_size))?,
+    })
+}
+
+impl From<TreeNodeType> for proto::NodeType {
+    fn from(value: TreeNodeType) -> Self {
+        match value {
+            TreeNodeType::Union => proto::NodeType::Union,
+            TreeNodeType::Leaf => proto::NodeType::Leaf,
+            TreeNodeType::ComputedUserset => proto::NodeType::ComputedUserset,
+            TreeNodeType::TupleToUserset => proto::NodeType::TupleToUserset,
+            TreeNodeType::Intersection => proto::NodeType::Intersection,
+            TreeNodeType::Exclusion => proto::NodeType::Exclusion,
+        }
+    }
+}
+
+impl From<Tree<String>> for proto::SubjectTree {
+    fn from(value: Tree<String>) -> Self {
+        Self {
+            node_type: proto::NodeType::from(value.node_type).into(),
+            subject: Some(value.subject.into()),
+            children: value.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::NodeType> for TreeNodeType {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::NodeType) -> Result<Self, Self::Error> {
+        match value {
+            proto::NodeType::Union => Ok(TreeNodeType::Union),
+            proto::NodeType::Leaf => Ok(TreeNodeType::Leaf),
+            proto::NodeType::ComputedUserset => Ok(TreeNodeType::ComputedUserset),
+            proto::NodeType::TupleToUserset => Ok(TreeNodeType::TupleToUserset),
+            proto::NodeType::Intersection => Ok(TreeNodeType::Intersection),
+            proto::NodeType::Exclusion => Ok(TreeNodeType::Exclusion),
+            proto::NodeType::Unspecified => Err(HeimdallError::MalformedInput),
+        }
+    }
+}
+
+impl TryFrom<proto::SubjectTree> for Tree<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::SubjectTree) -> Result<Self, Self::Error> {
+        Ok(Tree {
+            node_type: value.node_type().try_into()?,
+            subject: value
+                .subject
+                .ok_or(HeimdallError::NilSubjectError)?
+                .try_into()?,
+            children: value
+                .children
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<HeimdallResult<_>>()?,
+        })
+    }
+}
+
+impl From<Traversal> for proto::Traversal {
+    fn from(value: Traversal) -> Self {
+        match value {
+            Traversal::Unknown => proto::Traversal::Unspecified,
+            Traversal::SubjectSetExpand => proto::Traversal::SubjectSetExpand,
+            Traversal::ComputedUserset => proto::Traversal::ComputedUserset,
+            Traversal::TupleToUserset => proto::Traversal::TupleToUserset,
+        }
+    }
+}
+
+impl From<proto::Traversal> for Traversal {
+    fn from(value: proto::Traversal) -> Self {
+        match value {
+            proto::Traversal::Unspecified => Traversal::Unknown,
+            proto::Traversal::SubjectSetExpand => Traversal::SubjectSetExpand,
+            proto::Traversal::ComputedUserset => Traversal::ComputedUserset,
+            proto::Traversal::TupleToUserset => Traversal::TupleToUserset,
+        }
+    }
+}
+
+impl From<TraversalResult<String>> for proto::TraversalStep {
+    fn from(value: TraversalResult<String>) -> Self {
+        Self {
+            from: Some(value.from.into()),
+            to: Some(value.to.into()),
+            via: proto::Traversal::from(value.via).into(),
+            found: value.found,
+        }
+    }
+}
+
+impl TryFrom<proto::TraversalStep> for TraversalResult<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::TraversalStep) -> Result<Self, Self::Error> {
+        Ok(TraversalResult {
+            via: value.via().into(),
+            from: value
+                .from
+                .ok_or(HeimdallError::MalformedInput)?
+                .try_into()?,
+            to: value.to.ok_or(HeimdallError::MalformedInput)?.try_into()?,
+            found: value.found,
+        })
+    }
+}
+
+impl From<CheckExplanation<String>> for proto::CheckExplanation {
+    fn from(value: CheckExplanation<String>) -> Self {
+        let (reason, via) = match value.reason {
+            CheckReason::DirectTuple => (proto::CheckReason::DirectTuple, Traversal::Unknown),
+            CheckReason::Traversal(via) => (proto::CheckReason::Traversal, via),
+            CheckReason::Intersection => (proto::CheckReason::Intersection, Traversal::Unknown),
+            CheckReason::Exclusion => (proto::CheckReason::Exclusion, Traversal::Unknown),
+            CheckReason::Excluded => (proto::CheckReason::Excluded, Traversal::Unknown),
+            CheckReason::MaxDepthReached => {
+                (proto::CheckReason::MaxDepthReached, Traversal::Unknown)
+            }
+            CheckReason::NoPathFound => (proto::CheckReason::NoPathFound, Traversal::Unknown),
+        };
+        Self {
+            reason: reason.into(),
+            via: proto::Traversal::from(via).into(),
+            path: value.path.into_iter().map(Into::into).collect(),
+            tried: value.tried.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::CheckExplanation> for CheckExplanation<String> {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::CheckExplanation) -> Result<Self, Self::Error> {
+        let reason = match value.reason() {
+            proto::CheckReason::DirectTuple => CheckReason::DirectTuple,
+            proto::CheckReason::Traversal => CheckReason::Traversal(value.via().into()),
+            proto::CheckReason::Intersection => CheckReason::Intersection,
+            proto::CheckReason::Exclusion => CheckReason::Exclusion,
+            proto::CheckReason::Excluded => CheckReason::Excluded,
+            proto::CheckReason::MaxDepthReached => CheckReason::MaxDepthReached,
+            proto::CheckReason::NoPathFound => CheckReason::NoPathFound,
+            proto::CheckReason::Unspecified => return Err(HeimdallError::MalformedInput),
+        };
+        Ok(CheckExplanation {
+            reason,
+            path: value
+                .path
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<HeimdallResult<_>>()?,
+            tried: value
+                .tried
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<HeimdallResult<_>>()?,
+        })
+    }
+}
+
+impl From<Network> for proto::Network {
+    fn from(value: Network) -> Self {
+        Self {
+            id: value.id.to_string(),
+            created_at: value.created_at.to_rfc3339(),
+            updated_at: value.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+impl TryFrom<proto::ApiKeyScope> for ApiKeyScope {
+    type Error = HeimdallError;
+
+    fn try_from(value: proto::ApiKeyScope) -> Result<Self, Self::Error> {
+        match value {
+            proto::ApiKeyScope::ReadOnly => Ok(ApiKeyScope::ReadOnly),
+            proto::ApiKeyScope::Write => Ok(ApiKeyScope::Write),
+            proto::ApiKeyScope::Admin => Ok(ApiKeyScope::Admin),
+            proto::ApiKeyScope::Unspecified => Err(HeimdallError::MalformedInput),
+        }
+    }
+}
+
+impl From<ApiKeyScope> for proto::ApiKeyScope {
+    fn from(value: ApiKeyScope) -> Self {
+        match value {
+            ApiKeyScope::ReadOnly => proto::ApiKeyScope::ReadOnly,
+            ApiKeyScope::Write => proto::ApiKeyScope::Write,
+            ApiKeyScope::Admin => proto::ApiKeyScope::Admin,
+        }
+    }
+}
+
+impl From<ApiKey> for proto::ApiKey {
+    fn from(value: ApiKey) -> Self {
+        Self {
+            id: value.id.to_string(),
+            network_id: value.network_id.to_string(),
+            name: value.name,
+            scope: proto::ApiKeyScope::from(value.scope).into(),
+            created_at: value.created_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<ChangeAction> for proto::watch_response::Action {
+    fn from(value: ChangeAction) -> Self {
+        match value {
+            ChangeAction::Insert => Self::Insert,
+            ChangeAction::Delete => Self::Delete,
+        }
+    }
+}
+
+impl From<RelationTupleChange<String>> for proto::WatchResponse {
+    fn from(value: RelationTupleChange<String>) -> Self {
+        Self {
+            action: proto::watch_response::Action::from(value.action).into(),
+            relation_tuple: Some(value.relation_tuple.into()),
+            snaptoken: value.snaptoken.to_string(),
+        }
+    }
+}
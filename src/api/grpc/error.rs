@@ -0,0 +1,41 @@
+use tonic::Status;
+use tracing::error;
+
+use crate::error::HeimdallError;
+
+impl From<HeimdallError> for Status {
+    fn from(value: HeimdallError) -> Self {
+        let message = value.to_string().trim_end().to_string();
+        match value {
+            HeimdallError::NilSubjectError
+            | HeimdallError::MalformedInput
+            | HeimdallError::InvalidRelationTuple { .. }
+            | HeimdallError::BatchTooLarge { .. }
+            | HeimdallError::LookupTooLarge { .. }
+            | HeimdallError::Parse { .. }
+            | HeimdallError::NetworkMissing => Status::invalid_argument(message),
+            HeimdallError::NamespaceNotFound(_)
+            | HeimdallError::NetworkNotFound(_)
+            | HeimdallError::ApiKeyNotFound(_)
+            | HeimdallError::RelationNotFound { .. } => Status::not_found(message),
+            HeimdallError::NetworkAlreadyExists(_) => Status::already_exists(message),
+            HeimdallError::SnapshotExpired(_) => Status::failed_precondition(message),
+            HeimdallError::Unauthenticated => Status::unauthenticated(message),
+            HeimdallError::PermissionDenied(_) => Status::permission_denied(message),
+            // Server errors may carry connection strings or SQL, so they are
+            // only logged.
+            HeimdallError::Database(_)
+            | HeimdallError::Migration(_)
+            | HeimdallError::PendingMigrations(_)
+            | HeimdallError::Configuration(_)
+            | HeimdallError::InvalidNamespaceConfig(_)
+            | HeimdallError::InvalidSettings(_)
+            | HeimdallError::Io(_)
+            | HeimdallError::Transport(_)
+            | HeimdallError::Remote(_) => {
+                error!(error = %message, "request failed");
+                Status::internal("internal error")
+            }
+        }
+    }
+}
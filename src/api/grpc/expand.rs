@@ -0,0 +1,56 @@
+use tonic::{Request, Response, Status};
+
+use crate::{api::ApiState, error::HeimdallError, models::relation_tuple::SubjectSet};
+
+use super::{
+    context::request_context,
+    convert::consistency,
+    proto::{ExpandRequest, ExpandResponse, expand_service_server::ExpandService},
+};
+
+pub struct ExpandHandler {
+    state: ApiState,
+}
+
+impl ExpandHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl ExpandService for ExpandHandler {
+    async fn expand(
+        &self,
+        request: Request<ExpandRequest>,
+    ) -> Result<Response<ExpandResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+        let ctx = self
+            .state
+            .read_context(ctx, consistency(request.consistency)?)
+            .await?;
+
+        let subject_set: SubjectSet<String> = request
+            .subject_set
+            .ok_or(HeimdallError::MalformedInput)?
+            .try_into()?;
+        let subject_set = self
+            .state
+            .id_mapper
+            .to_uuids_readonly(&ctx, subject_set)
+            .await?;
+        let max_depth = (request.max_depth > 0).then_some(request.max_depth);
+
+        let tree = self
+            .state
+            .expand_engine
+            .build_tree(&ctx, &subject_set, max_depth)
+            .await?;
+        let tree = self.state.id_mapper.to_strings(&ctx, tree).await?;
+
+        Ok(Response::new(ExpandResponse {
+            tree: tree.map(Into::into),
+        }))
+    }
+}
@@ -0,0 +1,106 @@
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::ApiState,
+    error::HeimdallError,
+    models::relation_tuple::{Subject, SubjectSet},
+};
+
+use super::{
+    context::request_context,
+    convert::{consistency, pagination},
+    proto::{
+        ListObjectsRequest, ListObjectsResponse, ListSubjectsRequest, ListSubjectsResponse,
+        lookup_service_server::LookupService,
+    },
+};
+
+pub struct LookupHandler {
+    state: ApiState,
+}
+
+impl LookupHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl LookupService for LookupHandler {
+    async fn list_objects(
+        &self,
+        request: Request<ListObjectsRequest>,
+    ) -> Result<Response<ListObjectsResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+        let ctx = self
+            .state
+            .read_context(ctx, consistency(request.consistency)?)
+            .await?;
+
+        let subject: Subject<String> = request
+            .subject
+            .ok_or(HeimdallError::NilSubjectError)?
+            .try_into()?;
+        let subject = self
+            .state
+            .id_mapper
+            .to_uuids_readonly(&ctx, subject)
+            .await?;
+        let pagination = pagination(&request.page_token, request.page_size)?;
+        let max_depth = (request.max_depth > 0).then_some(request.max_depth);
+
+        let page = self
+            .state
+            .lookup_engine
+            .list_objects(
+                &ctx,
+                &request.namespace,
+                &request.relation,
+                &subject,
+                max_depth,
+                &pagination,
+            )
+            .await?;
+
+        Ok(Response::new(ListObjectsResponse {
+            objects: self.state.id_mapper.to_strings(&ctx, page.data).await?,
+            next_page_token: page.token,
+        }))
+    }
+
+    async fn list_subjects(
+        &self,
+        request: Request<ListSubjectsRequest>,
+    ) -> Result<Response<ListSubjectsResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+        let ctx = self
+            .state
+            .read_context(ctx, consistency(request.consistency)?)
+            .await?;
+
+        let subject_set: SubjectSet<String> = request
+            .subject_set
+            .ok_or(HeimdallError::NilSubjectError)?
+            .try_into()?;
+        let subject_set = self
+            .state
+            .id_mapper
+            .to_uuids_readonly(&ctx, subject_set)
+            .await?;
+        let pagination = pagination(&request.page_token, request.page_size)?;
+        let max_depth = (request.max_depth > 0).then_some(request.max_depth);
+
+        let page = self
+            .state
+            .lookup_engine
+            .list_subjects(&ctx, &subject_set, max_depth, &pagination)
+            .await?;
+
+        Ok(Response::new(ListSubjectsResponse {
+            subject_ids: self.state.id_mapper.to_strings(&ctx, page.data).await?,
+            next_page_token: page.token,
+        }))
+    }
+}
@@ -1 +1,54 @@
+mod api_key;
+mod check;
+mod context;
+mod convert;
+mod error;
+mod expand;
+mod lookup;
+mod network;
+mod read;
+mod watch;
+mod write;
 
+pub mod proto {
+    tonic::include_proto!("heimdall.v1");
+}
+
+use proto::{
+    api_key_service_server::ApiKeyServiceServer, check_service_server::CheckServiceServer,
+    expand_service_server::ExpandServiceServer, lookup_service_server::LookupServiceServer,
+    network_service_server::NetworkServiceServer, read_service_server::ReadServiceServer,
+    watch_service_server::WatchServiceServer, write_service_server::WriteServiceServer,
+};
+use tonic::service::Routes;
+
+use crate::api::ApiState;
+
+/// `ReadService`, `CheckService`, `ExpandService`, `LookupService` and
+/// `WatchService`.
+pub fn read_routes(state: ApiState) -> Routes {
+    Routes::new(ReadServiceServer::new(read::ReadHandler::new(
+        state.clone(),
+    )))
+    .add_service(CheckServiceServer::new(check::CheckHandler::new(
+        state.clone(),
+    )))
+    .add_service(ExpandServiceServer::new(expand::ExpandHandler::new(
+        state.clone(),
+    )))
+    .add_service(LookupServiceServer::new(lookup::LookupHandler::new(
+        state.clone(),
+    )))
+    .add_service(WatchServiceServer::new(watch::WatchHandler::new(state)))
+}
+
+/// `WriteService`, `NetworkService` and `ApiKeyService`.
+pub fn write_routes(state: ApiState) -> Routes {
+    Routes::new(WriteServiceServer::new(write::WriteHandler::new(
+        state.clone(),
+    )))
+    .add_service(NetworkServiceServer::new(network::NetworkHandler::new(
+        state.clone(),
+    )))
+    .add_service(ApiKeyServiceServer::new(api_key::ApiKeyHandler::new(state)))
+}
@@ -0,0 +1,95 @@
+use tonic::{Request, Response, Status};
+
+use crate::api::ApiState;
+
+use super::{
+    convert::{pagination, parse_uuid},
+    proto::{
+        CreateNetworkRequest, CreateNetworkResponse, DeleteNetworkRequest, DeleteNetworkResponse,
+        GetNetworkRequest, GetNetworkResponse, ListNetworksRequest, ListNetworksResponse,
+        network_service_server::NetworkService,
+    },
+};
+
+pub struct NetworkHandler {
+    state: ApiState,
+}
+
+impl NetworkHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl NetworkService for NetworkHandler {
+    async fn create_network(
+        &self,
+        request: Request<CreateNetworkRequest>,
+    ) -> Result<Response<CreateNetworkResponse>, Status> {
+        let id = match request.into_inner().id.as_str() {
+            "" => None,
+            id => Some(parse_uuid(id)?),
+        };
+
+        let network = self
+            .state
+            .services
+            .network_service
+            .create_network(id)
+            .await?;
+
+        Ok(Response::new(CreateNetworkResponse {
+            network: Some(network.into()),
+        }))
+    }
+
+    async fn get_network(
+        &self,
+        request: Request<GetNetworkRequest>,
+    ) -> Result<Response<GetNetworkResponse>, Status> {
+        let id = parse_uuid(&request.into_inner().id)?;
+
+        let network = self.state.services.network_service.get_network(id).await?;
+
+        Ok(Response::new(GetNetworkResponse {
+            network: Some(network.into()),
+        }))
+    }
+
+    async fn list_networks(
+        &self,
+        request: Request<ListNetworksRequest>,
+    ) -> Result<Response<ListNetworksResponse>, Status> {
+        let request = request.into_inner();
+
+        let pagination = pagination(&request.page_token, request.page_size)?;
+
+        let page = self
+            .state
+            .services
+            .network_service
+            .list_networks(&pagination)
+            .await?;
+
+        Ok(Response::new(ListNetworksResponse {
+            networks: page.data.into_iter().map(Into::into).collect(),
+            next_page_token: page.token,
+        }))
+    }
+
+    async fn delete_network(
+        &self,
+        request: Request<DeleteNetworkRequest>,
+    ) -> Result<Response<DeleteNetworkResponse>, Status> {
+        let id = parse_uuid(&request.into_inner().id)?;
+
+        self.state
+            .services
+            .network_service
+            .delete_network(id)
+            .await?;
+
+        Ok(Response::new(DeleteNetworkResponse {}))
+    }
+}
@@ -0,0 +1,61 @@
+use tonic::{Request, Response, Status};
+
+use crate::{api::ApiState, models::query::relation_tuple::RelationTupleQuery};
+
+use super::{
+    context::request_context,
+    convert::{consistency, pagination},
+    proto::{
+        ListRelationTuplesRequest, ListRelationTuplesResponse, read_service_server::ReadService,
+    },
+};
+
+pub struct ReadHandler {
+    state: ApiState,
+}
+
+impl ReadHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl ReadService for ReadHandler {
+    async fn list_relation_tuples(
+        &self,
+        request: Request<ListRelationTuplesRequest>,
+    ) -> Result<Response<ListRelationTuplesResponse>, Status> {
+        let ctx = request_context(&request)?;
+        let request = request.into_inner();
+        let ctx = self
+            .state
+            .read_context(ctx, consistency(request.consistency)?)
+            .await?;
+
+        let query = match request.relation_query {
+            Some(query) => RelationTupleQuery::<String>::try_from(query)?,
+            None => RelationTupleQuery {
+                namespace: None,
+                object: None,
+                relation: None,
+                subject: None,
+            },
+        };
+        let query = self.state.id_mapper.to_uuids_readonly(&ctx, query).await?;
+        let pagination = pagination(&request.page_token, request.page_size)?;
+
+        let page = self
+            .state
+            .services
+            .relation_tuple_service
+            .get_relation_tuples(&ctx, &query, &pagination)
+            .await?;
+        let relation_tuples = self.state.id_mapper.to_strings(&ctx, page.data).await?;
+
+        Ok(Response::new(ListRelationTuplesResponse {
+            relation_tuples: relation_tuples.into_iter().map(Into::into).collect(),
+            next_page_token: page.token,
+        }))
+    }
+}
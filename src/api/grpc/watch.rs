@@ -0,0 +1,58 @@
+use std::pin::Pin;
+
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::{api::ApiState, models::snapshot::SnapshotToken};
+
+use super::{
+    context::request_context,
+    proto::{WatchRequest, WatchResponse, watch_service_server::WatchService},
+};
+
+pub struct WatchHandler {
+    state: ApiState,
+}
+
+impl WatchHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl WatchService for WatchHandler {
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<WatchResponse, Status>> + Send>>;
+
+    async fn watch(
+        &self,
+        request: Request<WatchRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let ctx = request_context(&request)?;
+
+        let snapshot = match request.into_inner().snaptoken.as_str() {
+            "" => None,
+            token => Some(token.parse::<SnapshotToken>()?),
+        };
+        let cursor = self
+            .state
+            .services
+            .changelog_service
+            .change_cursor(&ctx, snapshot.as_ref())
+            .await?;
+
+        let id_mapper = self.state.id_mapper.clone();
+        let changes = self
+            .state
+            .watch_engine
+            .watch(ctx.clone(), cursor)
+            .then(move |change| {
+                let id_mapper = id_mapper.clone();
+                let ctx = ctx.clone();
+                async move { id_mapper.to_strings(&ctx, change?).await }
+            })
+            .map(|change| change.map(Into::into).map_err(Into::into));
+
+        Ok(Response::new(Box::pin(changes)))
+    }
+}
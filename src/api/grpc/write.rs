@@ -0,0 +1,100 @@
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::ApiState,
+    error::HeimdallError,
+    models::{query::relation_tuple::RelationTupleQuery, relation_tuple::RelationTuple},
+};
+
+use super::{
+    context::request_context,
+    proto::{
+        DeleteRelationTuplesRequest, DeleteRelationTuplesResponse, TransactRelationTuplesRequest,
+        TransactRelationTuplesResponse, relation_tuple_delta::Action,
+        write_service_server::WriteService,
+    },
+};
+
+pub struct WriteHandler {
+    state: ApiState,
+}
+
+impl WriteHandler {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl WriteService for WriteHandler {
+    /// All deltas are applied in one database transaction, deletes first.
+    async fn transact_relation_tuples(
+        &self,
+        request: Request<TransactRelationTuplesRequest>,
+    ) -> Result<Response<TransactRelationTuplesResponse>, Status> {
+        let ctx = request_context(&request)?;
+
+        let mut inserts = Vec::new();
+        let mut deletes = Vec::new();
+
+        for delta in request.into_inner().relation_tuple_deltas {
+            let action = delta.action();
+            let tuple: RelationTuple<String> = delta
+                .relation_tuple
+                .ok_or(HeimdallError::MalformedInput)?
+                .try_into()?;
+            match action {
+                Action::Insert => inserts.push(tuple),
+                Action::Delete => deletes.push(tuple),
+                Action::Unspecified => return Err(HeimdallError::MalformedInput.into()),
+            }
+        }
+
+        let id_mapper = &self.state.id_mapper;
+        let (inserts, mappings) = id_mapper.to_uuids(&ctx, inserts).await?;
+        let deletes = id_mapper.to_uuids_readonly(&ctx, deletes).await?;
+
+        let snapshot = self
+            .state
+            .services
+            .relation_tuple_service
+            .transact_relation_tuples(&ctx, &inserts, &deletes, &mappings)
+            .await?;
+
+        Ok(Response::new(TransactRelationTuplesResponse {
+            snaptoken: snapshot.to_string(),
+        }))
+    }
+
+    async fn delete_relation_tuples(
+        &self,
+        request: Request<DeleteRelationTuplesRequest>,
+    ) -> Result<Response<DeleteRelationTuplesResponse>, Status> {
+        let ctx = request_context(&request)?;
+
+        let query: RelationTupleQuery<String> = request
+            .into_inner()
+            .relation_query
+            .ok_or(HeimdallError::MalformedInput)?
+            .try_into()?;
+        if query.namespace.is_none()
+            && query.object.is_none()
+            && query.relation.is_none()
+            && query.subject.is_none()
+        {
+            return Err(HeimdallError::MalformedInput.into());
+        }
+        let query = self.state.id_mapper.to_uuids_readonly(&ctx, query).await?;
+
+        let snapshot = self
+            .state
+            .services
+            .relation_tuple_service
+            .delete_all_relation_tuples(&ctx, &query)
+            .await?;
+
+        Ok(Response::new(DeleteRelationTuplesResponse {
+            snaptoken: snapshot.to_string(),
+        }))
+    }
+}
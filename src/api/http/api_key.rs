@@ -0,0 +1,56 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::ApiState,
+    context::RequestContext,
+    error::HeimdallResult,
+    models::api_key::{ApiKey, ApiKeyScope, CreatedApiKey},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyBody {
+    pub scope: ApiKeyScope,
+    #[serde(default)]
+    pub name: String,
+}
+
+/// The response is the only time the secret is returned.
+pub async fn create_api_key(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Json(body): Json<CreateApiKeyBody>,
+) -> HeimdallResult<(StatusCode, Json<CreatedApiKey>)> {
+    let api_key = state
+        .services
+        .api_key_service
+        .create_api_key(&ctx, body.scope, &body.name)
+        .await?;
+    Ok((StatusCode::CREATED, Json(api_key)))
+}
+
+pub async fn list_api_keys(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+) -> HeimdallResult<Json<Vec<ApiKey>>> {
+    let api_keys = state.services.api_key_service.list_api_keys(&ctx).await?;
+    Ok(Json(api_keys))
+}
+
+pub async fn delete_api_key(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Path(id): Path<Uuid>,
+) -> HeimdallResult<StatusCode> {
+    state
+        .services
+        .api_key_service
+        .delete_api_key(&ctx, id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,151 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{ApiState, mapper::IdMapper},
+    context::RequestContext,
+    error::HeimdallResult,
+    models::{
+        check::{CheckExplanation, CheckResult},
+        relation_tuple::RelationTuple,
+    },
+};
+
+use super::relation_tuple::{ConsistencyParams, RelationTupleParams};
+
+#[derive(Debug, Deserialize)]
+pub struct DepthParams {
+    #[serde(rename = "max-depth")]
+    pub max_depth: Option<u32>,
+}
+
+/// `?explain=true` adds why each check was decided the way it was.
+#[derive(Debug, Deserialize)]
+pub struct ExplainParams {
+    #[serde(default)]
+    pub explain: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckResponse {
+    pub allowed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<CheckExplanation<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckBatchRequest {
+    pub tuples: Vec<RelationTuple<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckBatchResponse {
+    /// In the order of the request's tuples.
+    pub results: Vec<CheckResponse>,
+}
+
+pub async fn get_check(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(params): Query<RelationTupleParams>,
+    Query(depth): Query<DepthParams>,
+    Query(explain): Query<ExplainParams>,
+    Query(consistency): Query<ConsistencyParams>,
+) -> HeimdallResult<Json<CheckResponse>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    check(
+        &state,
+        &ctx,
+        params.require_tuple()?,
+        depth.max_depth,
+        explain.explain,
+    )
+    .await
+}
+
+pub async fn post_check(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(depth): Query<DepthParams>,
+    Query(explain): Query<ExplainParams>,
+    Query(consistency): Query<ConsistencyParams>,
+    Json(tuple): Json<RelationTuple<String>>,
+) -> HeimdallResult<Json<CheckResponse>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    check(&state, &ctx, tuple, depth.max_depth, explain.explain).await
+}
+
+async fn check(
+    state: &ApiState,
+    ctx: &RequestContext,
+    tuple: RelationTuple<String>,
+    max_depth: Option<u32>,
+    explain: bool,
+) -> HeimdallResult<Json<CheckResponse>> {
+    let mapped = state
+        .id_mapper
+        .to_uuids_readonly(ctx, tuple.clone())
+        .await?;
+    let result = state
+        .check_engine
+        .check(ctx, &mapped, max_depth, explain)
+        .await?;
+    let explanation = if explain {
+        let given = IdMapper::given_ids(&tuple, &mapped);
+        Some(
+            state
+                .id_mapper
+                .to_strings_given(ctx, result.explanation(), given)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(Json(CheckResponse {
+        allowed: result.is_allowed(),
+        explanation,
+    }))
+}
+
+pub async fn post_check_batch(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(depth): Query<DepthParams>,
+    Query(explain): Query<ExplainParams>,
+    Query(consistency): Query<ConsistencyParams>,
+    Json(request): Json<CheckBatchRequest>,
+) -> HeimdallResult<Json<CheckBatchResponse>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    let tuples = state
+        .id_mapper
+        .to_uuids_readonly(&ctx, request.tuples.clone())
+        .await?;
+    let results = state
+        .check_engine
+        .check_batch(&ctx, &tuples, depth.max_depth, explain.explain)
+        .await?;
+    let explanations = if explain.explain {
+        let given = IdMapper::given_ids(&request.tuples, &tuples);
+        let explanations: Vec<_> = results.iter().map(CheckResult::explanation).collect();
+        state
+            .id_mapper
+            .to_strings_given(&ctx, explanations, given)
+            .await?
+    } else {
+        Vec::new()
+    };
+    let mut explanations = explanations.into_iter();
+
+    Ok(Json(CheckBatchResponse {
+        results: results
+            .iter()
+            .map(|result| CheckResponse {
+                allowed: result.is_allowed(),
+                explanation: explanations.next(),
+            })
+            .collect(),
+    }))
+}
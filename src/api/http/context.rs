@@ -0,0 +1,19 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{context::RequestContext, error::HeimdallError, middlewares::RequestMetadata};
+
+/// Reads the metadata attached by `RequestContextLayer`.
+impl<S> FromRequestParts<S> for RequestContext
+where
+    S: Send + Sync,
+{
+    type Rejection = HeimdallError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<RequestMetadata>()
+            .ok_or(HeimdallError::NetworkMissing)?
+            .context()
+    }
+}
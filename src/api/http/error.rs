@@ -0,0 +1,69 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use tracing::error;
+
+use crate::error::HeimdallError;
+
+impl HeimdallError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            HeimdallError::NilSubjectError
+            | HeimdallError::MalformedInput
+            | HeimdallError::InvalidRelationTuple { .. }
+            | HeimdallError::BatchTooLarge { .. }
+            | HeimdallError::LookupTooLarge { .. }
+            | HeimdallError::SnapshotExpired(_)
+            | HeimdallError::Parse { .. }
+            | HeimdallError::NetworkMissing => StatusCode::BAD_REQUEST,
+            HeimdallError::NamespaceNotFound(_)
+            | HeimdallError::NetworkNotFound(_)
+            | HeimdallError::ApiKeyNotFound(_)
+            | HeimdallError::RelationNotFound { .. } => StatusCode::NOT_FOUND,
+            HeimdallError::NetworkAlreadyExists(_) => StatusCode::CONFLICT,
+            HeimdallError::Unauthenticated => StatusCode::UNAUTHORIZED,
+            HeimdallError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            HeimdallError::Database(_)
+            | HeimdallError::Migration(_)
+            | HeimdallError::PendingMigrations(_)
+            | HeimdallError::Configuration(_)
+            | HeimdallError::InvalidNamespaceConfig(_)
+            | HeimdallError::InvalidSettings(_)
+            | HeimdallError::Io(_)
+            | HeimdallError::Transport(_)
+            | HeimdallError::Remote(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// The message shown to the client. Server errors may carry connection
+    /// strings or SQL, so they are only logged and the client gets the generic
+    /// reason phrase.
+    pub(super) fn client_message(&self) -> String {
+        let status = self.status_code();
+        if status.is_server_error() {
+            error!(error = %self, "request failed");
+            status.canonical_reason().unwrap_or_default().to_string()
+        } else {
+            self.to_string().trim_end().to_string()
+        }
+    }
+}
+
+impl IntoResponse for HeimdallError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let message = self.client_message();
+
+        let body = json!({
+            "error": {
+                "code": status.as_u16(),
+                "message": message,
+            }
+        });
+
+        (status, Json(body)).into_response()
+    }
+}
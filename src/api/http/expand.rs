@@ -0,0 +1,29 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+
+use crate::{
+    api::ApiState,
+    context::RequestContext,
+    error::HeimdallResult,
+    models::{expand::Tree, relation_tuple::SubjectSet},
+};
+
+use super::{check::DepthParams, relation_tuple::ConsistencyParams};
+
+pub async fn get_expand(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(subject_set): Query<SubjectSet<String>>,
+    Query(depth): Query<DepthParams>,
+    Query(consistency): Query<ConsistencyParams>,
+) -> HeimdallResult<Json<Option<Tree<String>>>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    let subject_set = state.id_mapper.to_uuids_readonly(&ctx, subject_set).await?;
+    let tree = state
+        .expand_engine
+        .build_tree(&ctx, &subject_set, depth.max_depth)
+        .await?;
+    Ok(Json(state.id_mapper.to_strings(&ctx, tree).await?))
+}
@@ -0,0 +1,94 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+
+use crate::{
+    api::ApiState,
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::{relation_tuple::SubjectSet, response::PaginatedResponse},
+};
+
+use super::{
+    check::DepthParams,
+    relation_tuple::{ConsistencyParams, PaginationParams, RelationTupleParams},
+};
+
+/// `?namespace=document&relation=viewer&subject_id=alice`, or a subject set
+/// instead of the subject ID. The object is what is looked up, so it must not
+/// be given.
+pub async fn list_objects(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(params): Query<RelationTupleParams>,
+    Query(pagination): Query<PaginationParams>,
+    Query(depth): Query<DepthParams>,
+    Query(consistency): Query<ConsistencyParams>,
+) -> HeimdallResult<Json<PaginatedResponse<Vec<String>>>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    let (Some(namespace), None, Some(relation), Some(subject)) = (
+        &params.namespace,
+        &params.object,
+        &params.relation,
+        params.subject()?,
+    ) else {
+        return Err(HeimdallError::MalformedInput);
+    };
+    let subject = state.id_mapper.to_uuids_readonly(&ctx, subject).await?;
+
+    let page = state
+        .lookup_engine
+        .list_objects(
+            &ctx,
+            namespace,
+            relation,
+            &subject,
+            depth.max_depth,
+            &pagination.pagination()?,
+        )
+        .await?;
+
+    Ok(Json(PaginatedResponse {
+        data: state.id_mapper.to_strings(&ctx, page.data).await?,
+        token: page.token,
+    }))
+}
+
+/// `?namespace=document&object=readme.md&relation=viewer`. The subject is
+/// what is looked up, so it must not be given.
+pub async fn list_subjects(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(params): Query<RelationTupleParams>,
+    Query(pagination): Query<PaginationParams>,
+    Query(depth): Query<DepthParams>,
+    Query(consistency): Query<ConsistencyParams>,
+) -> HeimdallResult<Json<PaginatedResponse<Vec<String>>>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    let (Some(namespace), Some(object), Some(relation), None) = (
+        &params.namespace,
+        &params.object,
+        &params.relation,
+        params.subject()?,
+    ) else {
+        return Err(HeimdallError::MalformedInput);
+    };
+    let subject_set = SubjectSet::new(namespace.clone(), object.clone(), relation.clone());
+    let subject_set = state.id_mapper.to_uuids_readonly(&ctx, subject_set).await?;
+
+    let page = state
+        .lookup_engine
+        .list_subjects(
+            &ctx,
+            &subject_set,
+            depth.max_depth,
+            &pagination.pagination()?,
+        )
+        .await?;
+
+    Ok(Json(PaginatedResponse {
+        data: state.id_mapper.to_strings(&ctx, page.data).await?,
+        token: page.token,
+    }))
+}
@@ -1 +1,63 @@
+mod api_key;
+mod check;
+mod context;
+mod error;
+mod expand;
+mod lookup;
+mod network;
+mod relation_tuple;
+mod watch;
 
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+
+use crate::api::ApiState;
+
+/// Listing and watching relation tuples, check, expand and lookups.
+pub fn read_router(state: ApiState) -> Router {
+    Router::new()
+        .route(
+            "/relation-tuples",
+            get(relation_tuple::get_relation_tuples).head(relation_tuple::exists_relation_tuples),
+        )
+        .route(
+            "/relation-tuples/check",
+            get(check::get_check).post(check::post_check),
+        )
+        .route(
+            "/relation-tuples/check/batch",
+            post(check::post_check_batch),
+        )
+        .route("/relation-tuples/expand", get(expand::get_expand))
+        .route("/relation-tuples/objects", get(lookup::list_objects))
+        .route("/relation-tuples/subjects", get(lookup::list_subjects))
+        .route("/relation-tuples/watch", get(watch::watch_relation_tuples))
+        .with_state(state)
+}
+
+/// Writing and deleting relation tuples, and managing networks and API keys.
+pub fn write_router(state: ApiState) -> Router {
+    Router::new()
+        .route(
+            "/relation-tuples",
+            put(relation_tuple::write_relation_tuple)
+                .patch(relation_tuple::patch_relation_tuples)
+                .delete(relation_tuple::delete_relation_tuples),
+        )
+        .route(
+            "/networks",
+            get(network::list_networks).post(network::create_network),
+        )
+        .route(
+            "/networks/{id}",
+            get(network::get_network).delete(network::delete_network),
+        )
+        .route(
+            "/api-keys",
+            get(api_key::list_api_keys).post(api_key::create_api_key),
+        )
+        .route("/api-keys/{id}", delete(api_key::delete_api_key))
+        .with_state(state)
+}
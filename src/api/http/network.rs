@@ -0,0 +1,62 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::ApiState,
+    error::HeimdallResult,
+    models::{network::Network, response::PaginatedResponse},
+};
+
+use super::relation_tuple::PaginationParams;
+
+/// Body of `POST /networks`. Send `{}` to let the server pick the id.
+#[derive(Debug, Deserialize)]
+pub struct CreateNetworkBody {
+    #[serde(default)]
+    pub id: Option<Uuid>,
+}
+
+pub async fn create_network(
+    State(state): State<ApiState>,
+    Json(body): Json<CreateNetworkBody>,
+) -> HeimdallResult<(StatusCode, Json<Network>)> {
+    let network = state
+        .services
+        .network_service
+        .create_network(body.id)
+        .await?;
+    Ok((StatusCode::CREATED, Json(network)))
+}
+
+pub async fn list_networks(
+    State(state): State<ApiState>,
+    Query(pagination): Query<PaginationParams>,
+) -> HeimdallResult<Json<PaginatedResponse<Vec<Network>>>> {
+    let response = state
+        .services
+        .network_service
+        .list_networks(&pagination.pagination()?)
+        .await?;
+    Ok(Json(response))
+}
+
+pub async fn get_network(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> HeimdallResult<Json<Network>> {
+    let network = state.services.network_service.get_network(id).await?;
+    Ok(Json(network))
+}
+
+pub async fn delete_network(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> HeimdallResult<StatusCode> {
+    state.services.network_service.delete_network(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,296 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+};
+use serde::Deserialize;
+
+use crate::{
+    api::ApiState,
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::{
+        change::ChangeAction,
+        query::{TokenPagination, relation_tuple::RelationTupleQuery},
+        relation_tuple::{RelationTuple, Subject, SubjectID, SubjectSet},
+        response::PaginatedResponse,
+        snapshot::{Consistency, SnapshotToken},
+    },
+};
+
+/// Response header carrying the snapshot token of a write.
+const SNAPTOKEN_HEADER: HeaderName = HeaderName::from_static("x-heimdall-snaptoken");
+
+/// Relation tuple filters as they appear in the query string, e.g.
+/// `?namespace=document&relation=viewer&subject_set.namespace=group&...`.
+#[derive(Debug, Deserialize)]
+pub struct RelationTupleParams {
+    pub namespace: Option<String>,
+    pub object: Option<String>,
+    pub relation: Option<String>,
+    pub subject_id: Option<String>,
+    #[serde(rename = "subject_set.namespace")]
+    pub subject_set_namespace: Option<String>,
+    #[serde(rename = "subject_set.object")]
+    pub subject_set_object: Option<String>,
+    #[serde(rename = "subject_set.relation")]
+    pub subject_set_relation: Option<String>,
+}
+
+impl RelationTupleParams {
+    /// A subject is either an ID or a complete subject set, never both.
+    pub fn subject(&self) -> HeimdallResult<Option<Subject<String>>> {
+        match (
+            &self.subject_id,
+            &self.subject_set_namespace,
+            &self.subject_set_object,
+            &self.subject_set_relation,
+        ) {
+            (None, None, None, None) => Ok(None),
+            (Some(id), None, None, None) => Ok(Some(Subject::Direct(SubjectID::new(id.clone())))),
+            (None, Some(namespace), Some(object), Some(relation)) => Ok(Some(Subject::Set(
+                SubjectSet::new(namespace.clone(), object.clone(), relation.clone()),
+            ))),
+            _ => Err(HeimdallError::MalformedInput),
+        }
+    }
+
+    pub fn query(&self) -> HeimdallResult<RelationTupleQuery<String>> {
+        Ok(RelationTupleQuery {
+            namespace: self.namespace.clone(),
+            object: self.object.clone(),
+            relation: self.relation.clone(),
+            subject: self.subject()?,
+        })
+    }
+
+    /// Returns the tuple when every part of it is given.
+    pub fn tuple(&self) -> HeimdallResult<Option<RelationTuple<String>>> {
+        let subject = self.subject()?;
+        match (&self.namespace, &self.object, &self.relation, subject) {
+            (Some(namespace), Some(object), Some(relation), Some(subject)) => {
+                Ok(Some(RelationTuple {
+                    namespace: namespace.clone(),
+                    object: object.clone(),
+                    relation: relation.clone(),
+                    subject,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Like `tuple`, but every part is required.
+    pub fn require_tuple(&self) -> HeimdallResult<RelationTuple<String>> {
+        self.tuple()?.ok_or(HeimdallError::MalformedInput)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaginationParams {
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl PaginationParams {
+    pub fn pagination(&self) -> HeimdallResult<TokenPagination> {
+        let last_id = match self.page_token {
+            Some(ref token) => TokenPagination::decode_page_token(token)?,
+            None => None,
+        };
+        Ok(TokenPagination {
+            last_id,
+            page_size: TokenPagination::checked_page_size(self.page_size)?,
+        })
+    }
+}
+
+/// `?at_least_as_fresh=<token>` or `?at_exact_snapshot=<token>`, with tokens
+/// from the `x-heimdall-snaptoken` header of writes. Reads the latest snapshot
+/// when neither is given.
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyParams {
+    pub at_least_as_fresh: Option<SnapshotToken>,
+    pub at_exact_snapshot: Option<SnapshotToken>,
+}
+
+impl ConsistencyParams {
+    pub fn consistency(&self) -> HeimdallResult<Consistency> {
+        match (self.at_least_as_fresh, self.at_exact_snapshot) {
+            (None, None) => Ok(Consistency::Latest),
+            (Some(token), None) => Ok(Consistency::AtLeastAsFresh(token)),
+            (None, Some(token)) => Ok(Consistency::AtExactSnapshot(token)),
+            (Some(_), Some(_)) => Err(HeimdallError::MalformedInput),
+        }
+    }
+}
+
+fn snaptoken_header(snapshot: SnapshotToken) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    // Tokens are always ASCII.
+    if let Ok(value) = HeaderValue::from_str(&snapshot.to_string()) {
+        headers.insert(SNAPTOKEN_HEADER, value);
+    }
+    headers
+}
+
+pub async fn get_relation_tuples(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(params): Query<RelationTupleParams>,
+    Query(pagination): Query<PaginationParams>,
+    Query(consistency): Query<ConsistencyParams>,
+) -> HeimdallResult<Json<PaginatedResponse<Vec<RelationTuple<String>>>>> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    let query = state
+        .id_mapper
+        .to_uuids_readonly(&ctx, params.query()?)
+        .await?;
+    let page = state
+        .services
+        .relation_tuple_service
+        .get_relation_tuples(&ctx, &query, &pagination.pagination()?)
+        .await?;
+    Ok(Json(PaginatedResponse {
+        data: state.id_mapper.to_strings(&ctx, page.data).await?,
+        token: page.token,
+    }))
+}
+
+pub async fn exists_relation_tuples(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(params): Query<RelationTupleParams>,
+    Query(consistency): Query<ConsistencyParams>,
+) -> HeimdallResult<StatusCode> {
+    let ctx = state.read_context(ctx, consistency.consistency()?).await?;
+    let query = state
+        .id_mapper
+        .to_uuids_readonly(&ctx, params.query()?)
+        .await?;
+    let exists = state
+        .services
+        .relation_tuple_service
+        .exists_relation_tuples(&ctx, &query)
+        .await?;
+    Ok(if exists {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    })
+}
+
+pub async fn write_relation_tuple(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Json(tuple): Json<RelationTuple<String>>,
+) -> HeimdallResult<(StatusCode, HeaderMap, Json<RelationTuple<String>>)> {
+    let (mapped, mappings) = state.id_mapper.to_uuids(&ctx, tuple.clone()).await?;
+    let snapshot = state
+        .services
+        .relation_tuple_service
+        .write_relation_tuples(&ctx, &[mapped], &mappings)
+        .await?;
+    Ok((StatusCode::CREATED, snaptoken_header(snapshot), Json(tuple)))
+}
+
+/// One entry of a `PATCH /relation-tuples` body, e.g.
+/// `{"action": "delete", "relation_tuple": {...}}`.
+#[derive(Debug, Deserialize)]
+pub struct RelationTupleDelta {
+    pub action: ChangeAction,
+    pub relation_tuple: RelationTuple<String>,
+}
+
+/// Applies every delta of the body in one transaction, deletes first.
+pub async fn patch_relation_tuples(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Json(deltas): Json<Vec<RelationTupleDelta>>,
+) -> HeimdallResult<(StatusCode, HeaderMap)> {
+    let mut inserts = Vec::new();
+    let mut deletes = Vec::new();
+    for delta in deltas {
+        match delta.action {
+            ChangeAction::Insert => inserts.push(delta.relation_tuple),
+            ChangeAction::Delete => deletes.push(delta.relation_tuple),
+        }
+    }
+    let (inserts, mappings) = state.id_mapper.to_uuids(&ctx, inserts).await?;
+    let deletes = state.id_mapper.to_uuids_readonly(&ctx, deletes).await?;
+
+    let snapshot = state
+        .services
+        .relation_tuple_service
+        .transact_relation_tuples(&ctx, &inserts, &deletes, &mappings)
+        .await?;
+    Ok((StatusCode::NO_CONTENT, snaptoken_header(snapshot)))
+}
+
+/// Deletes the tuple when every part of it is given, and every tuple matching
+/// the filters otherwise. At least one filter is required so that a bare
+/// request cannot wipe the whole network.
+pub async fn delete_relation_tuples(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    Query(params): Query<RelationTupleParams>,
+) -> HeimdallResult<(StatusCode, HeaderMap)> {
+    let relation_tuple_service = &state.services.relation_tuple_service;
+
+    if let Some(tuple) = params.tuple()? {
+        let tuple = state.id_mapper.to_uuids_readonly(&ctx, tuple).await?;
+        let snapshot = relation_tuple_service
+            .delete_relation_tuples(&ctx, &[tuple])
+            .await?;
+        return Ok((StatusCode::NO_CONTENT, snaptoken_header(snapshot)));
+    }
+
+    let query = params.query()?;
+    if query.namespace.is_none()
+        && query.object.is_none()
+        && query.relation.is_none()
+        && query.subject.is_none()
+    {
+        return Err(HeimdallError::MalformedInput);
+    }
+    let query = state.id_mapper.to_uuids_readonly(&ctx, query).await?;
+
+    let snapshot = relation_tuple_service
+        .delete_all_relation_tuples(&ctx, &query)
+        .await?;
+    Ok((StatusCode::NO_CONTENT, snaptoken_header(snapshot)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Uri;
+
+    use super::*;
+
+    fn consistency(query: &str) -> HeimdallResult<Consistency> {
+        let uri: Uri = format!("/relation-tuples?{query}").parse().unwrap();
+        Query::<ConsistencyParams>::try_from_uri(&uri)
+            .map_err(|_| HeimdallError::MalformedInput)?
+            .consistency()
+    }
+
+    #[test]
+    fn parses_consistency_parameters() {
+        let token: SnapshotToken = "v1.65e0280b4bd94".parse().unwrap();
+
+        assert_eq!(consistency("").unwrap(), Consistency::Latest);
+        assert_eq!(
+            consistency("at_least_as_fresh=v1.65e0280b4bd94").unwrap(),
+            Consistency::AtLeastAsFresh(token)
+        );
+        assert_eq!(
+            consistency("at_exact_snapshot=v1.65e0280b4bd94").unwrap(),
+            Consistency::AtExactSnapshot(token)
+        );
+        assert!(
+            consistency("at_least_as_fresh=v1.65e0280b4bd94&at_exact_snapshot=v1.65e0280b4bd94")
+                .is_err()
+        );
+        assert!(consistency("at_exact_snapshot=latest").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::Deserialize;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{
+    api::ApiState,
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::snapshot::SnapshotToken,
+};
+
+/// Sent by a reconnecting `EventSource` with the id of the last event it got.
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+#[derive(Debug, Deserialize)]
+pub struct WatchParams {
+    /// Streams changes committed after this snapshot token. Without one the
+    /// stream starts with the next change.
+    pub snaptoken: Option<SnapshotToken>,
+}
+
+/// Server-sent events named `insert` or `delete`, each carrying the change as
+/// JSON and its changelog id as the event id, so reconnecting resumes where
+/// the stream left off. A failure is sent as a final `error` event.
+pub async fn watch_relation_tuples(
+    State(state): State<ApiState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Query(params): Query<WatchParams>,
+) -> HeimdallResult<Sse<impl Stream<Item = Result<Event, axum::Error>>>> {
+    let cursor = match headers.get(LAST_EVENT_ID_HEADER) {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .ok_or(HeimdallError::MalformedInput)?,
+        None => {
+            state
+                .services
+                .changelog_service
+                .change_cursor(&ctx, params.snaptoken.as_ref())
+                .await?
+        }
+    };
+
+    let id_mapper = state.id_mapper.clone();
+    let events = state
+        .watch_engine
+        .watch(ctx.clone(), cursor)
+        .then(move |change| {
+            let id_mapper = id_mapper.clone();
+            let ctx = ctx.clone();
+            async move { id_mapper.to_strings(&ctx, change?).await }
+        })
+        .map(|change| match change {
+            Ok(change) => Event::default()
+                .id(change.id.to_string())
+                .event(change.action.to_string())
+                .json_data(&change),
+            Err(e) => Ok(Event::default().event("error").data(e.client_message())),
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,147 @@
+use std::{collections::HashMap, sync::Arc};
+
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::{query::TokenPagination, relation_tuple::MapIds},
+    persistance::schema::UuidMapping,
+    services::traits::UuidMappingManager,
+};
+
+/// Maps the string object and subject IDs the API takes to the UUIDs tuples
+/// are stored with, and back. IDs may be any non-empty string. One that
+/// already is a UUID in its canonical form is used as is, so tuples written
+/// with UUIDs keep working.
+#[derive(Clone)]
+pub struct IdMapper {
+    uuid_mapping_service: Arc<dyn UuidMappingManager>,
+}
+
+impl IdMapper {
+    pub fn new(uuid_mapping_service: Arc<dyn UuidMappingManager>) -> Self {
+        Self {
+            uuid_mapping_service,
+        }
+    }
+
+    /// Maps the IDs of a value that is about to be written. The mappings the
+    /// strings can be read back with are returned rather than stored, so the
+    /// write stores them in its own transaction and a rejected write leaves
+    /// none behind.
+    pub async fn to_uuids<M: MapIds<String>>(
+        &self,
+        ctx: &RequestContext,
+        value: M,
+    ) -> HeimdallResult<(M::Mapped<Uuid>, Vec<UuidMapping>)> {
+        let (uuids, mappings) = self.uuids(ctx, &value).await?;
+        Ok((value.map_ids(&mut |id| uuids[&id]), mappings))
+    }
+
+    /// Maps the IDs of a value that is only read, e.g. a check or a filter,
+    /// without storing mappings.
+    pub async fn to_uuids_readonly<M: MapIds<String>>(
+        &self,
+        ctx: &RequestContext,
+        value: M,
+    ) -> HeimdallResult<M::Mapped<Uuid>> {
+        let (uuids, _) = self.uuids(ctx, &value).await?;
+        Ok(value.map_ids(&mut |id| uuids[&id]))
+    }
+
+    pub async fn to_strings<M: MapIds<Uuid>>(
+        &self,
+        ctx: &RequestContext,
+        value: M,
+    ) -> HeimdallResult<M::Mapped<String>> {
+        self.to_strings_given(ctx, value, HashMap::new()).await
+    }
+
+    /// Like `to_strings`, but maps the IDs in `given` to their strings without
+    /// a lookup. Read-only requests pass what they were sent, see
+    /// `given_ids`, since those strings may never have been stored.
+    pub async fn to_strings_given<M: MapIds<Uuid>>(
+        &self,
+        ctx: &RequestContext,
+        value: M,
+        mut given: HashMap<Uuid, String>,
+    ) -> HeimdallResult<M::Mapped<String>> {
+        let mut ids = Vec::new();
+        value.for_each_id(&mut |id| {
+            if !given.contains_key(id) {
+                ids.push(*id)
+            }
+        });
+        ids.sort_unstable();
+        ids.dedup();
+
+        let strings = self
+            .uuid_mapping_service
+            .map_uuids_to_strings(
+                ctx,
+                &ids,
+                &TokenPagination {
+                    last_id: None,
+                    page_size: None,
+                },
+            )
+            .await?;
+        given.extend(ids.into_iter().zip(strings));
+
+        Ok(value.map_ids(&mut |id| given[&id].clone()))
+    }
+
+    /// Pairs the IDs of a value as it was sent with the ones it was mapped to.
+    pub fn given_ids<S: MapIds<String>, U: MapIds<Uuid>>(
+        strings: &S,
+        uuids: &U,
+    ) -> HashMap<Uuid, String> {
+        let mut sent = Vec::new();
+        strings.for_each_id(&mut |id| sent.push(id.clone()));
+        let mut mapped = Vec::new();
+        uuids.for_each_id(&mut |id| mapped.push(*id));
+        mapped.into_iter().zip(sent).collect()
+    }
+
+    /// The UUID of every ID, and the mappings of the IDs that are not UUIDs.
+    async fn uuids<M: MapIds<String>>(
+        &self,
+        ctx: &RequestContext,
+        value: &M,
+    ) -> HeimdallResult<(HashMap<String, Uuid>, Vec<UuidMapping>)> {
+        let mut uuids = HashMap::new();
+        let mut unmapped = Vec::new();
+        let mut has_empty = false;
+        value.for_each_id(&mut |id| match Uuid::try_parse(id) {
+            Ok(uuid) if uuid.to_string() == *id => {
+                uuids.insert(id.clone(), uuid);
+            }
+            _ if id.is_empty() => has_empty = true,
+            _ => unmapped.push(id.clone()),
+        });
+        if has_empty {
+            return Err(HeimdallError::MalformedInput);
+        }
+        unmapped.sort_unstable();
+        unmapped.dedup();
+
+        let mapped = self
+            .uuid_mapping_service
+            .map_strings_to_uuids_readonly(ctx, &unmapped)
+            .await?;
+        let mappings = unmapped
+            .into_iter()
+            .zip(mapped)
+            .map(|(string_representation, id)| {
+                uuids.insert(string_representation.clone(), id);
+                UuidMapping {
+                    id,
+                    string_representation,
+                }
+            })
+            .collect();
+
+        Ok((uuids, mappings))
+    }
+}
@@ -1,3 +1,11 @@
-mod cmd;
+pub mod cmd;
 mod grpc;
 mod http;
+mod mapper;
+mod server;
+mod state;
+
+pub use self::{
+    server::{ApiKind, ApiServer},
+    state::ApiState,
+};
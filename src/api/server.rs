@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use axum::middleware::from_fn_with_state;
+use clap::ValueEnum;
+use tokio::{net::TcpListener, sync::watch};
+use tonic::service::Routes;
+use tower_http::trace::TraceLayer;
+use tracing::info;
+
+use crate::{
+    error::{HeimdallError, HeimdallResult},
+    middlewares::{Authenticator, Protocol, RequestContextLayer, authenticate},
+    settings::ApiListenSettings,
+};
+
+use super::{ApiState, grpc, http};
+
+/// The halves of the API that are served on separate listeners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ApiKind {
+    /// Listing and watching relation tuples, check, expand and lookups.
+    Read,
+    /// Writing relation tuples, and managing networks and API keys.
+    Write,
+}
+
+impl std::fmt::Display for ApiKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKind::Read => write!(f, "read"),
+            ApiKind::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// Serves one API on its HTTP and gRPC listeners. Every server builds its own
+/// middleware stacks, so the read and write APIs can share a process or run
+/// in separate ones.
+pub struct ApiServer {
+    kind: ApiKind,
+    state: ApiState,
+    authenticator: Arc<Authenticator>,
+    context_layer: RequestContextLayer,
+}
+
+impl ApiServer {
+    pub fn new(
+        kind: ApiKind,
+        state: ApiState,
+        authenticator: Arc<Authenticator>,
+        context_layer: RequestContextLayer,
+    ) -> Self {
+        Self {
+            kind,
+            state,
+            authenticator,
+            context_layer,
+        }
+    }
+
+    fn http_router(&self) -> axum::Router {
+        let router = match self.kind {
+            ApiKind::Read => http::read_router(self.state.clone()),
+            ApiKind::Write => http::write_router(self.state.clone()),
+        };
+
+        router
+            .layer(from_fn_with_state(
+                (self.authenticator.clone(), Protocol::Http),
+                authenticate,
+            ))
+            .layer(TraceLayer::new_for_http())
+            .layer(self.context_layer.clone())
+    }
+
+    /// The request context and trace layers go on the tonic server itself, see
+    /// `serve`, so that they wrap the gRPC routing as well.
+    fn grpc_routes(&self) -> Routes {
+        let routes = match self.kind {
+            ApiKind::Read => grpc::read_routes(self.state.clone()),
+            ApiKind::Write => grpc::write_routes(self.state.clone()),
+        };
+
+        let router = routes.into_axum_router().layer(from_fn_with_state(
+            (self.authenticator.clone(), Protocol::Grpc),
+            authenticate,
+        ));
+        Routes::from(router)
+    }
+
+    /// Serves until `shutdown` fires, then lets in-flight requests finish.
+    pub async fn serve(
+        self,
+        listen: &ApiListenSettings,
+        shutdown: watch::Receiver<()>,
+    ) -> HeimdallResult<()> {
+        let http_address = listen.http.address()?;
+        let http_listener = TcpListener::bind(http_address).await?;
+        let http_server = axum::serve(http_listener, self.http_router())
+            .with_graceful_shutdown(wait_for_shutdown(shutdown.clone()));
+        info!(api = %self.kind, address = %http_address, "serving HTTP");
+
+        let grpc_address = listen.grpc.address()?;
+        let grpc_server = tonic::transport::Server::builder()
+            .layer(self.context_layer.clone())
+            .layer(TraceLayer::new_for_grpc())
+            .add_routes(self.grpc_routes())
+            .serve_with_shutdown(grpc_address, wait_for_shutdown(shutdown));
+        info!(api = %self.kind, address = %grpc_address, "serving gRPC");
+
+        tokio::try_join!(
+            async { http_server.await.map_err(HeimdallError::from) },
+            async { grpc_server.await.map_err(HeimdallError::from) },
+        )?;
+        Ok(())
+    }
+}
+
+async fn wait_for_shutdown(mut shutdown: watch::Receiver<()>) {
+    // The sender is only dropped once serving is over, so an error here means
+    // the same thing as a shutdown.
+    let _ = shutdown.changed().await;
+}
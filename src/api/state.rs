@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use crate::{
+    context::RequestContext,
+    engines::{check::CheckEngine, expand::ExpandEngine, lookup::LookupEngine, watch::WatchEngine},
+    error::HeimdallResult,
+    models::snapshot::Consistency,
+    services::Services,
+};
+
+use super::mapper::IdMapper;
+
+/// Shared by the HTTP and gRPC handlers.
+#[derive(Clone)]
+pub struct ApiState {
+    pub services: Services,
+    pub check_engine: CheckEngine,
+    pub expand_engine: ExpandEngine,
+    pub lookup_engine: LookupEngine,
+    pub watch_engine: WatchEngine,
+    pub id_mapper: IdMapper,
+}
+
+impl ApiState {
+    pub fn new(
+        services: Services,
+        max_depth: u32,
+        max_batch_size: usize,
+        poll_interval: Duration,
+    ) -> Self {
+        let check_engine = CheckEngine::new(services.clone(), max_depth, max_batch_size);
+        Self {
+            lookup_engine: LookupEngine::new(services.clone(), check_engine.clone(), max_depth),
+            check_engine,
+            expand_engine: ExpandEngine::new(services.clone(), max_depth),
+            watch_engine: WatchEngine::new(services.clone(), poll_interval),
+            id_mapper: IdMapper::new(services.uuid_mapping_service.clone()),
+            services,
+        }
+    }
+
+    /// `ctx` with its reads evaluated at `consistency`. Exact snapshots the
+    /// network has deleted tuples since are rejected.
+    pub async fn read_context(
+        &self,
+        ctx: RequestContext,
+        consistency: Consistency,
+    ) -> HeimdallResult<RequestContext> {
+        if consistency.snapshot().is_some() {
+            let last_delete = self.services.changelog_service.last_delete(&ctx).await?;
+            consistency.check_readable(last_delete.as_ref())?;
+        }
+        Ok(ctx.with_consistency(consistency))
+    }
+}
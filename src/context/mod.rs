@@ -2,3 +2,9 @@ mod request;
 
 #[allow(unused)]
 pub use self::request::RequestContext;
+
+/// Header (HTTP) and metadata key (gRPC) carrying the caller's network.
+pub const NETWORK_ID_HEADER: &str = "x-heimdall-network-id";
+
+/// Header propagating a caller-supplied request id, echoed on responses.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
@@ -1,15 +1,47 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::models::snapshot::Consistency;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestContext {
     network_id: Uuid,
     request_id: String,
     trace_id: String,
+    /// Snapshot the request's reads are evaluated at.
+    #[serde(skip)]
+    consistency: Consistency,
 }
 
+#[allow(unused)]
 impl RequestContext {
+    pub fn new(network_id: Uuid, request_id: String, trace_id: String) -> Self {
+        Self {
+            network_id,
+            request_id,
+            trace_id,
+            consistency: Consistency::default(),
+        }
+    }
+
+    pub fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
     pub fn network_id(&self) -> &Uuid {
         &self.network_id
     }
+
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn consistency(&self) -> &Consistency {
+        &self.consistency
+    }
 }
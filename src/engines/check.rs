@@ -1 +1,598 @@
+use std::collections::HashMap;
 
+use tracing::{info_span, trace};
+
+use crate::{
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::{
+        check::{CheckReason, CheckResult},
+        namespace::Rewrite,
+        query::relation_tuple::RelationTupleQuery,
+        relation_tuple::RelationTuple,
+        traversal::TraversalResult,
+    },
+    services::Services,
+};
+
+#[allow(unused)]
+pub const DEFAULT_MAX_DEPTH: u32 = 5;
+
+#[allow(unused)]
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
+/// Answers shared by the checks of one request, so a batch does not walk the
+/// same tuple twice.
+#[derive(Default)]
+struct CheckCache {
+    /// Whether results keep the traversal steps, which is only needed to
+    /// explain them.
+    explain: bool,
+    /// Whether a tuple is stored as-is.
+    direct: HashMap<RelationTuple, bool>,
+    /// Membership of a tuple, walked with the given depth left.
+    members: HashMap<(RelationTuple, u32), CheckResult>,
+}
+
+/// Answers "is subject S in relation R of namespace:object O?" by evaluating the
+/// relation's rewrite from the namespace configuration: direct tuples, nested
+/// subject sets, computed usersets and tuple-to-usersets are walked until
+/// `max_depth` levels have been explored, and combined by union, intersection
+/// and exclusion.
+#[derive(Clone)]
+#[allow(unused)]
+pub struct CheckEngine {
+    services: Services,
+    max_depth: u32,
+    max_batch_size: usize,
+}
+
+#[allow(unused)]
+impl CheckEngine {
+    pub fn new(services: Services, max_depth: u32, max_batch_size: usize) -> Self {
+        Self {
+            services,
+            max_depth,
+            max_batch_size,
+        }
+    }
+
+    /// Checks `tuple`, walking at most `max_depth` levels. With `explain` the
+    /// result keeps the traversal steps the decision rests on, or the ones
+    /// tried in vain.
+    pub async fn check(
+        &self,
+        ctx: &RequestContext,
+        tuple: &RelationTuple,
+        max_depth: Option<u32>,
+        explain: bool,
+    ) -> HeimdallResult<CheckResult> {
+        let span = info_span!("check", namespace = %tuple.namespace, relation = %tuple.relation);
+        let _guard = span.enter();
+
+        let rest_depth = self.rest_depth(max_depth);
+        let mut cache = CheckCache {
+            explain,
+            ..Default::default()
+        };
+        self.check_is_member(ctx, tuple, rest_depth, &mut cache)
+            .await
+    }
+
+    /// Checks every tuple like `check` and returns the results in the same
+    /// order. Whether the tuples are stored as-is is looked up with one query,
+    /// and tuples walked by several checks are only walked once.
+    pub async fn check_batch(
+        &self,
+        ctx: &RequestContext,
+        tuples: &[RelationTuple],
+        max_depth: Option<u32>,
+        explain: bool,
+    ) -> HeimdallResult<Vec<CheckResult>> {
+        let span = info_span!("check_batch", count = tuples.len());
+        let _guard = span.enter();
+
+        if tuples.len() > self.max_batch_size {
+            return Err(HeimdallError::BatchTooLarge {
+                size: tuples.len(),
+                max: self.max_batch_size,
+            });
+        }
+        let rest_depth = self.rest_depth(max_depth);
+
+        let exists = self
+            .services
+            .relation_tuple_service
+            .exists_relation_tuples_batch(ctx, tuples)
+            .await?;
+        let mut cache = CheckCache {
+            explain,
+            direct: tuples.iter().cloned().zip(exists).collect(),
+            ..Default::default()
+        };
+
+        let mut results = Vec::with_capacity(tuples.len());
+        for tuple in tuples {
+            results.push(
+                self.check_is_member(ctx, tuple, rest_depth, &mut cache)
+                    .await?,
+            );
+        }
+        Ok(results)
+    }
+
+    /// A missing or zero depth falls back to the engine default, and the
+    /// requested depth can never exceed it.
+    fn rest_depth(&self, max_depth: Option<u32>) -> u32 {
+        match max_depth {
+            Some(depth) if depth > 0 => depth.min(self.max_depth),
+            _ => self.max_depth,
+        }
+    }
+
+    async fn check_is_member(
+        &self,
+        ctx: &RequestContext,
+        tuple: &RelationTuple,
+        rest_depth: u32,
+        cache: &mut CheckCache,
+    ) -> HeimdallResult<CheckResult> {
+        if rest_depth == 0 {
+            trace!(namespace = %tuple.namespace, relation = %tuple.relation, "max depth reached");
+            return Ok(CheckResult::denied(CheckReason::MaxDepthReached));
+        }
+        let key = (tuple.clone(), rest_depth);
+        if let Some(result) = cache.members.get(&key) {
+            return Ok(result.clone());
+        }
+
+        let relation = self
+            .services
+            .namespace_service
+            .get_relation(ctx, &tuple.namespace, &tuple.relation)
+            .await?;
+
+        let result = self
+            .check_rewrite(ctx, tuple, &relation.rewrite(), rest_depth, cache)
+            .await?;
+        cache.members.insert(key, result.clone());
+        Ok(result)
+    }
+
+    async fn check_rewrite(
+        &self,
+        ctx: &RequestContext,
+        tuple: &RelationTuple,
+        rewrite: &Rewrite,
+        rest_depth: u32,
+        cache: &mut CheckCache,
+    ) -> HeimdallResult<CheckResult> {
+        let traversal_service = &self.services.traversal_service;
+
+        match rewrite {
+            Rewrite::This => {
+                if self.check_direct(ctx, tuple, cache).await? {
+                    return Ok(CheckResult::allowed(CheckReason::DirectTuple));
+                }
+                let mut results = traversal_service
+                    .traverse_subject_set_expansion(ctx, tuple)
+                    .await?;
+                // An empty relation names an object, not its subjects.
+                results.retain(|result| !result.to.relation.is_empty());
+                self.check_traversal_results(ctx, results, rest_depth, cache)
+                    .await
+            }
+            Rewrite::ComputedUserset { relation } => {
+                let results = traversal_service
+                    .traverse_subject_set_rewrite(ctx, tuple, std::slice::from_ref(relation))
+                    .await?;
+                self.check_traversal_results(ctx, results, rest_depth, cache)
+                    .await
+            }
+            Rewrite::TupleToUserset {
+                tupleset,
+                computed_userset,
+            } => {
+                let results = traversal_service
+                    .traverse_tuple_to_userset(ctx, tuple, tupleset, computed_userset)
+                    .await?;
+                self.check_traversal_results(ctx, results, rest_depth, cache)
+                    .await
+            }
+            Rewrite::Union { children } => {
+                let mut reason = CheckReason::NoPathFound;
+                let mut tried = Vec::new();
+                for child in children {
+                    let result =
+                        Box::pin(self.check_rewrite(ctx, tuple, child, rest_depth, cache)).await?;
+                    if result.is_allowed() {
+                        return Ok(result);
+                    }
+                    if result.reason == CheckReason::MaxDepthReached {
+                        reason = CheckReason::MaxDepthReached;
+                    }
+                    if cache.explain {
+                        tried.extend(result.tried);
+                    }
+                }
+                Ok(CheckResult::denied(reason).with_tried(tried))
+            }
+            Rewrite::Intersection { children } => {
+                if children.is_empty() {
+                    return Ok(CheckResult::denied(CheckReason::NoPathFound));
+                }
+                let mut path = Vec::new();
+                for child in children {
+                    let result =
+                        Box::pin(self.check_rewrite(ctx, tuple, child, rest_depth, cache)).await?;
+                    if !result.is_allowed() {
+                        return Ok(result);
+                    }
+                    if cache.explain {
+                        path.extend(result.path);
+                    }
+                }
+                Ok(CheckResult::allowed(CheckReason::Intersection).with_path(path))
+            }
+            Rewrite::Exclusion { base, subtract } => {
+                let base =
+                    Box::pin(self.check_rewrite(ctx, tuple, base, rest_depth, cache)).await?;
+                if !base.is_allowed() {
+                    return Ok(base);
+                }
+                let subtract =
+                    Box::pin(self.check_rewrite(ctx, tuple, subtract, rest_depth, cache)).await?;
+                // A subtracted branch that ran out of depth may still contain
+                // the subject, so access is only granted once it is ruled out.
+                match (subtract.is_allowed(), subtract.reason) {
+                    (true, _) => {
+                        Ok(CheckResult::denied(CheckReason::Excluded).with_path(subtract.path))
+                    }
+                    (false, CheckReason::MaxDepthReached) => {
+                        Ok(CheckResult::denied(CheckReason::MaxDepthReached)
+                            .with_tried(subtract.tried))
+                    }
+                    (false, _) => {
+                        Ok(CheckResult::allowed(CheckReason::Exclusion).with_path(base.path))
+                    }
+                }
+            }
+        }
+    }
+
+    async fn check_direct(
+        &self,
+        ctx: &RequestContext,
+        tuple: &RelationTuple,
+        cache: &mut CheckCache,
+    ) -> HeimdallResult<bool> {
+        if let Some(exists) = cache.direct.get(tuple) {
+            return Ok(*exists);
+        }
+        let exists = self
+            .services
+            .relation_tuple_service
+            .exists_relation_tuples(ctx, &RelationTupleQuery::from(tuple))
+            .await?;
+        cache.direct.insert(tuple.clone(), exists);
+        Ok(exists)
+    }
+
+    /// Every traversal already checks one level further for a direct match.
+    /// That match only decides when the stored tuple counts as-is, i.e. the
+    /// target relation's rewrite grants its stored subjects. Everything else
+    /// is walked recursively.
+    async fn check_traversal_results(
+        &self,
+        ctx: &RequestContext,
+        results: Vec<TraversalResult>,
+        rest_depth: u32,
+        cache: &mut CheckCache,
+    ) -> HeimdallResult<CheckResult> {
+        for result in results.iter().filter(|result| result.found) {
+            let relation = self
+                .services
+                .namespace_service
+                .get_relation(ctx, &result.to.namespace, &result.to.relation)
+                .await?;
+            if relation.rewrite().grants_stored() {
+                let path = if cache.explain {
+                    vec![result.clone()]
+                } else {
+                    Vec::new()
+                };
+                return Ok(CheckResult::allowed(CheckReason::Traversal(result.via)).with_path(path));
+            }
+        }
+
+        let mut reason = CheckReason::NoPathFound;
+        let mut tried = Vec::new();
+
+        for result in results {
+            let nested =
+                Box::pin(self.check_is_member(ctx, &result.to, rest_depth - 1, cache)).await?;
+            if nested.is_allowed() {
+                let via = result.via;
+                let mut path = Vec::new();
+                if cache.explain {
+                    path.push(result);
+                    path.extend(nested.path);
+                }
+                return Ok(CheckResult::allowed(CheckReason::Traversal(via)).with_path(path));
+            }
+            if nested.reason == CheckReason::MaxDepthReached {
+                reason = CheckReason::MaxDepthReached;
+            }
+            if cache.explain {
+                tried.push(result);
+                tried.extend(nested.tried);
+            }
+        }
+
+        Ok(CheckResult::denied(reason).with_tried(tried))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::traversal::Traversal,
+        services::memory::{context, services, tuple},
+    };
+
+    use super::*;
+
+    const NAMESPACES: &str = r#"
+namespaces:
+  - name: user
+  - name: group
+    relations:
+      - name: member
+      - name: approved
+      - name: approved_member
+        rewrite:
+          type: intersection
+          children:
+            - type: this
+            - type: computed_userset
+              relation: approved
+  - name: folder
+    relations:
+      - name: viewer
+  - name: document
+    relations:
+      - name: owner
+      - name: parent
+        subject_sets:
+          - namespace: folder
+      - name: editor
+        rewrite:
+          type: union
+          children:
+            - type: this
+            - type: computed_userset
+              relation: owner
+      - name: viewer
+        rewrite:
+          type: union
+          children:
+            - type: this
+            - type: computed_userset
+              relation: editor
+            - type: tuple_to_userset
+              tupleset: parent
+              computed_userset: viewer
+      - name: banned
+      - name: commenter
+        rewrite:
+          type: exclusion
+          base:
+            type: computed_userset
+            relation: viewer
+          subtract:
+            type: computed_userset
+            relation: banned
+"#;
+
+    async fn check_at_depth(tuples: &[&str], checked: &str, max_depth: u32) -> CheckResult {
+        let engine = CheckEngine::new(services(NAMESPACES, tuples), 5, 10);
+        engine
+            .check(&context(), &tuple(checked), Some(max_depth), true)
+            .await
+            .unwrap()
+    }
+
+    async fn check(tuples: &[&str], checked: &str) -> CheckResult {
+        check_at_depth(tuples, checked, 5).await
+    }
+
+    #[tokio::test]
+    async fn allows_stored_tuples() {
+        let result = check(&["document:d#viewer@alice"], "document:d#viewer@alice").await;
+        assert!(result.is_allowed());
+        assert_eq!(result.reason, CheckReason::DirectTuple);
+    }
+
+    #[tokio::test]
+    async fn follows_nested_subject_sets() {
+        let result = check(
+            &["document:d#viewer@group:g#member", "group:g#member@alice"],
+            "document:d#viewer@alice",
+        )
+        .await;
+        assert!(result.is_allowed());
+        assert_eq!(
+            result.reason,
+            CheckReason::Traversal(Traversal::SubjectSetExpand)
+        );
+    }
+
+    #[tokio::test]
+    async fn follows_computed_usersets() {
+        let result = check(&["document:d#owner@alice"], "document:d#viewer@alice").await;
+        assert!(result.is_allowed());
+        assert_eq!(
+            result.reason,
+            CheckReason::Traversal(Traversal::ComputedUserset)
+        );
+        assert_eq!(result.path.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn follows_tuple_to_usersets() {
+        let result = check(
+            &["document:d#parent@folder:f#", "folder:f#viewer@alice"],
+            "document:d#viewer@alice",
+        )
+        .await;
+        assert!(result.is_allowed());
+        assert_eq!(
+            result.reason,
+            CheckReason::Traversal(Traversal::TupleToUserset)
+        );
+    }
+
+    #[tokio::test]
+    async fn denies_when_no_path_reaches_the_subject() {
+        let result = check(
+            &["document:d#owner@alice", "document:d#parent@folder:f#"],
+            "document:d#viewer@bob",
+        )
+        .await;
+        assert!(!result.is_allowed());
+        assert_eq!(result.reason, CheckReason::NoPathFound);
+        assert!(!result.tried.is_empty());
+    }
+
+    #[tokio::test]
+    async fn denies_when_the_depth_runs_out() {
+        let tuples = [
+            "document:d#viewer@group:a#member",
+            "group:a#member@group:b#member",
+            "group:b#member@group:c#member",
+            "group:c#member@alice",
+        ];
+        let result = check_at_depth(&tuples, "document:d#viewer@alice", 2).await;
+        assert!(!result.is_allowed());
+        assert_eq!(result.reason, CheckReason::MaxDepthReached);
+        assert!(
+            check_at_depth(&tuples, "document:d#viewer@alice", 3)
+                .await
+                .is_allowed()
+        );
+    }
+
+    #[tokio::test]
+    async fn only_explains_when_asked() {
+        let engine = CheckEngine::new(services(NAMESPACES, &["document:d#owner@alice"]), 5, 10);
+        let result = engine
+            .check(&context(), &tuple("document:d#viewer@alice"), None, false)
+            .await
+            .unwrap();
+        assert!(result.is_allowed());
+        assert!(result.path.is_empty());
+    }
+
+    #[tokio::test]
+    async fn checks_batches_in_order() {
+        let engine = CheckEngine::new(services(NAMESPACES, &["document:d#owner@alice"]), 5, 2);
+        let results = engine
+            .check_batch(
+                &context(),
+                &[
+                    tuple("document:d#viewer@bob"),
+                    tuple("document:d#viewer@alice"),
+                ],
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(!results[0].is_allowed());
+        assert!(results[1].is_allowed());
+
+        let too_large = vec![tuple("document:d#viewer@bob"); 3];
+        assert!(matches!(
+            engine
+                .check_batch(&context(), &too_large, None, false)
+                .await,
+            Err(HeimdallError::BatchTooLarge { size: 3, max: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn intersections_need_every_branch() {
+        let tuples = [
+            "group:g#approved_member@alice",
+            "group:g#approved@alice",
+            "group:g#approved_member@bob",
+        ];
+        let result = check(&tuples, "group:g#approved_member@alice").await;
+        assert!(result.is_allowed());
+        assert_eq!(result.reason, CheckReason::Intersection);
+        assert!(
+            !check(&tuples, "group:g#approved_member@bob")
+                .await
+                .is_allowed()
+        );
+    }
+
+    #[tokio::test]
+    async fn exclusions_remove_subtracted_subjects() {
+        let tuples = [
+            "document:d#owner@alice",
+            "document:d#owner@bob",
+            "document:d#banned@bob",
+        ];
+        let result = check(&tuples, "document:d#commenter@alice").await;
+        assert!(result.is_allowed());
+        assert_eq!(result.reason, CheckReason::Exclusion);
+
+        let result = check(&tuples, "document:d#commenter@bob").await;
+        assert!(!result.is_allowed());
+        assert_eq!(result.reason, CheckReason::Excluded);
+    }
+
+    #[tokio::test]
+    async fn exclusions_fail_closed_when_the_subtracted_branch_runs_out_of_depth() {
+        let tuples = [
+            "document:d#owner@alice",
+            "document:d#banned@group:a#member",
+            "group:a#member@group:b#member",
+            "group:b#member@bob",
+        ];
+        let result = check_at_depth(&tuples, "document:d#commenter@alice", 3).await;
+        assert!(!result.is_allowed());
+        assert_eq!(result.reason, CheckReason::MaxDepthReached);
+        assert!(
+            check_at_depth(&tuples, "document:d#commenter@alice", 4)
+                .await
+                .is_allowed()
+        );
+    }
+
+    #[tokio::test]
+    async fn walks_subject_sets_after_a_stored_match_that_does_not_grant() {
+        let result = check(
+            &[
+                "document:d#viewer@group:a#approved_member",
+                "group:a#approved_member@user",
+                "document:d#viewer@group:b#member",
+                "group:b#member@user",
+            ],
+            "document:d#viewer@user",
+        )
+        .await;
+        assert!(result.is_allowed());
+    }
+
+    #[tokio::test]
+    async fn skips_object_only_subject_sets() {
+        let result = check(
+            &["document:d#viewer@folder:f#", "document:d#viewer@user"],
+            "document:d#viewer@other",
+        )
+        .await;
+        assert!(!result.is_allowed());
+    }
+}
@@ -1 +1,334 @@
+use std::collections::HashSet;
 
+use tracing::{info_span, trace};
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::{
+        expand::{Tree, TreeNodeType},
+        namespace::Rewrite,
+        query::{TokenPagination, relation_tuple::RelationTupleQuery},
+        relation_tuple::{Subject, SubjectSet},
+    },
+    services::Services,
+};
+
+const EXPAND_PAGE_SIZE: i32 = 100;
+
+/// Builds the userset tree of a subject set by following the relation's rewrite
+/// from the namespace configuration. Stored subjects become leaves, and nested
+/// subject sets are expanded until `max_depth` levels have been built.
+/// Intersections and exclusions keep their own nodes, so the tree shows which
+/// subjects are required or removed rather than a flattened result.
+#[derive(Clone)]
+#[allow(unused)]
+pub struct ExpandEngine {
+    services: Services,
+    max_depth: u32,
+}
+
+#[allow(unused)]
+impl ExpandEngine {
+    pub fn new(services: Services, max_depth: u32) -> Self {
+        Self {
+            services,
+            max_depth,
+        }
+    }
+
+    /// Expands `subject_set`, building at most `max_depth` levels. A missing or
+    /// zero depth falls back to the engine default, and the requested depth can
+    /// never exceed it. Returns `None` when the subject set has already been
+    /// expanded higher up the tree.
+    pub async fn build_tree(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+        max_depth: Option<u32>,
+    ) -> HeimdallResult<Option<Tree>> {
+        let span = info_span!("expand", namespace = %subject_set.namespace, relation = %subject_set.relation);
+        let _guard = span.enter();
+
+        let rest_depth = match max_depth {
+            Some(depth) if depth > 0 => depth.min(self.max_depth),
+            _ => self.max_depth,
+        };
+
+        let mut visited = HashSet::new();
+        self.build_subtree(ctx, subject_set, rest_depth, &mut visited)
+            .await
+    }
+
+    async fn build_subtree(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+        rest_depth: u32,
+        visited: &mut HashSet<Uuid>,
+    ) -> HeimdallResult<Option<Tree>> {
+        let id = subject_set.unique_id();
+        if rest_depth == 0 || visited.contains(&id) {
+            trace!(subject_set = %subject_set, "skipping subject set");
+            return Ok(None);
+        }
+
+        let relation = self
+            .services
+            .namespace_service
+            .get_relation(ctx, &subject_set.namespace, &subject_set.relation)
+            .await?;
+
+        // Only the current path is skipped, so a subject set reached through
+        // several branches is expanded under each of them.
+        visited.insert(id);
+        let tree = self
+            .expand_rewrite(ctx, subject_set, &relation.rewrite(), rest_depth, visited)
+            .await;
+        visited.remove(&id);
+
+        tree.map(Some)
+    }
+
+    async fn expand_rewrite(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+        rewrite: &Rewrite,
+        rest_depth: u32,
+        visited: &mut HashSet<Uuid>,
+    ) -> HeimdallResult<Tree> {
+        let subject = Subject::Set(subject_set.clone());
+
+        match rewrite {
+            Rewrite::This => {
+                let mut children = Vec::new();
+                for stored in self.fetch_subjects(ctx, subject_set).await? {
+                    children.push(
+                        self.expand_subject(ctx, stored, rest_depth, visited)
+                            .await?,
+                    );
+                }
+                Ok(Tree::union(subject, children))
+            }
+            Rewrite::ComputedUserset { relation } => {
+                let computed = SubjectSet::new(
+                    subject_set.namespace.clone(),
+                    subject_set.object,
+                    relation.clone(),
+                );
+                let child = self
+                    .expand_subject(ctx, Subject::Set(computed), rest_depth, visited)
+                    .await?;
+                Ok(Tree {
+                    node_type: TreeNodeType::ComputedUserset,
+                    subject,
+                    children: vec![child],
+                })
+            }
+            Rewrite::TupleToUserset {
+                tupleset,
+                computed_userset,
+            } => {
+                let tupleset = SubjectSet::new(
+                    subject_set.namespace.clone(),
+                    subject_set.object,
+                    tupleset.clone(),
+                );
+                let mut children = Vec::new();
+                for stored in self.fetch_subjects(ctx, &tupleset).await? {
+                    // Only subject sets point at other objects; plain subject
+                    // IDs stored under a tupleset carry no namespace to follow.
+                    if let Subject::Set(parent) = stored {
+                        let computed = SubjectSet::new(
+                            parent.namespace,
+                            parent.object,
+                            computed_userset.clone(),
+                        );
+                        children.push(
+                            self.expand_subject(ctx, Subject::Set(computed), rest_depth, visited)
+                                .await?,
+                        );
+                    }
+                }
+                Ok(Tree {
+                    node_type: TreeNodeType::TupleToUserset,
+                    subject,
+                    children,
+                })
+            }
+            Rewrite::Union { children } => {
+                let mut nodes = Vec::new();
+                for child in children {
+                    let tree =
+                        Box::pin(self.expand_rewrite(ctx, subject_set, child, rest_depth, visited))
+                            .await?;
+                    // Stored subjects are listed directly under the union
+                    // rather than under a nested union of the same subject set.
+                    match child {
+                        Rewrite::This => nodes.extend(tree.children),
+                        _ => nodes.push(tree),
+                    }
+                }
+                Ok(Tree::union(subject, nodes))
+            }
+            Rewrite::Intersection { children } => {
+                let mut nodes = Vec::with_capacity(children.len());
+                for child in children {
+                    nodes.push(
+                        Box::pin(self.expand_rewrite(ctx, subject_set, child, rest_depth, visited))
+                            .await?,
+                    );
+                }
+                Ok(Tree {
+                    node_type: TreeNodeType::Intersection,
+                    subject,
+                    children: nodes,
+                })
+            }
+            Rewrite::Exclusion { base, subtract } => {
+                let base =
+                    Box::pin(self.expand_rewrite(ctx, subject_set, base, rest_depth, visited))
+                        .await?;
+                let subtract =
+                    Box::pin(self.expand_rewrite(ctx, subject_set, subtract, rest_depth, visited))
+                        .await?;
+                Ok(Tree {
+                    node_type: TreeNodeType::Exclusion,
+                    subject,
+                    children: vec![base, subtract],
+                })
+            }
+        }
+    }
+
+    /// Expands a nested subject set one level further down, or returns it as a
+    /// leaf once the depth is used up or it already appears higher up the tree.
+    /// Object-only subject sets name no relation to expand and stay leaves.
+    async fn expand_subject(
+        &self,
+        ctx: &RequestContext,
+        subject: Subject,
+        rest_depth: u32,
+        visited: &mut HashSet<Uuid>,
+    ) -> HeimdallResult<Tree> {
+        match subject {
+            Subject::Set(ref nested) if rest_depth > 1 && !nested.relation.is_empty() => Ok(
+                Box::pin(self.build_subtree(ctx, nested, rest_depth - 1, visited))
+                    .await?
+                    .unwrap_or_else(|| Tree::leaf(subject.clone())),
+            ),
+            subject => Ok(Tree::leaf(subject)),
+        }
+    }
+
+    async fn fetch_subjects(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+    ) -> HeimdallResult<Vec<Subject>> {
+        let query = RelationTupleQuery {
+            namespace: Some(subject_set.namespace.clone()),
+            object: Some(subject_set.object),
+            relation: Some(subject_set.relation.clone()),
+            subject: None,
+        };
+
+        let mut subjects = Vec::new();
+        let mut pagination = TokenPagination {
+            last_id: None,
+            page_size: Some(EXPAND_PAGE_SIZE),
+        };
+
+        loop {
+            let page = self
+                .services
+                .relation_tuple_service
+                .get_relation_tuples(ctx, &query, &pagination)
+                .await?;
+
+            subjects.extend(page.data.into_iter().map(|tuple| tuple.subject));
+
+            match TokenPagination::decode_page_token(&page.token)? {
+                Some(last_id) => pagination.last_id = Some(last_id),
+                None => break,
+            }
+        }
+
+        Ok(subjects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::memory::{context, services, subject_set};
+
+    use super::*;
+
+    const NAMESPACES: &str = r#"
+namespaces:
+  - name: user
+  - name: folder
+  - name: document
+    relations:
+      - name: viewer
+  - name: group
+    relations:
+      - name: member
+"#;
+
+    #[tokio::test]
+    async fn keeps_object_only_subject_sets_as_leaves() {
+        let engine = ExpandEngine::new(services(NAMESPACES, &["document:d#viewer@folder:f#"]), 5);
+        let tree = engine
+            .build_tree(&context(), &subject_set("document:d#viewer"), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].node_type, TreeNodeType::Leaf);
+        assert_eq!(
+            tree.children[0].subject,
+            Subject::Set(subject_set("folder:f#"))
+        );
+    }
+
+    #[tokio::test]
+    async fn expands_subject_sets_under_every_branch() {
+        let engine = ExpandEngine::new(
+            services(
+                NAMESPACES,
+                &[
+                    "document:d#viewer@group:a#member",
+                    "document:d#viewer@group:b#member",
+                    "group:b#member@group:a#member",
+                    "group:a#member@group:a#member",
+                    "group:a#member@alice",
+                ],
+            ),
+            5,
+        );
+        let tree = engine
+            .build_tree(&context(), &subject_set("document:d#viewer"), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let group_a = &tree.children[0];
+        let group_b = &tree.children[1];
+        assert_eq!(group_a.node_type, TreeNodeType::Union);
+        assert_eq!(group_b.children.len(), 1);
+        assert_eq!(group_b.children[0].node_type, TreeNodeType::Union);
+        for group_a in [group_a, &group_b.children[0]] {
+            // The cycle back to itself stays a leaf.
+            assert_eq!(group_a.children.len(), 2);
+            assert_eq!(group_a.children[0].node_type, TreeNodeType::Leaf);
+            assert_eq!(
+                group_a.children[0].subject,
+                Subject::Set(subject_set("group:a#member"))
+            );
+            assert_eq!(group_a.children[1].node_type, TreeNodeType::Leaf);
+        }
+    }
+}
@@ -0,0 +1,770 @@
+use std::collections::{HashMap, HashSet, hash_map::Entry};
+
+use tracing::{info_span, trace};
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::{
+        namespace::{Namespace, Rewrite},
+        query::TokenPagination,
+        relation_tuple::{RelationTuple, Subject, SubjectSet},
+        response::PaginatedResponse,
+    },
+    services::Services,
+};
+
+use super::check::CheckEngine;
+
+const LOOKUP_PAGE_SIZE: i32 = 100;
+
+/// The most relations of objects `list_objects` walks through. Every page
+/// walks the graph from the subject again, so this bounds the work per page.
+pub const MAX_REACHED_RELATIONS: usize = 10_000;
+
+/// A tuple-to-userset turned around: a subject of `computed_userset` on a
+/// parent is a subject of `relation` on every object of `namespace` storing
+/// that parent under `tupleset`.
+struct ReverseTupleToUserset {
+    namespace: String,
+    tupleset: String,
+    relation: String,
+    exact: bool,
+}
+
+/// The rewrites of the namespace configuration turned around, so the relation
+/// graph can be walked from a subject to the objects it reaches. A rule is not
+/// exact when it passes through an intersection or an exclusion, whose other
+/// branches may still rule the subject out.
+#[derive(Default)]
+struct ReverseRewrites {
+    /// `(namespace, relation)` of the relations whose stored subjects count.
+    this: HashMap<(String, String), bool>,
+    /// `(namespace, relation)` to the relations of the same object computed
+    /// from it.
+    computed_usersets: HashMap<(String, String), Vec<(String, bool)>>,
+    /// Computed relation to the tuple-to-usersets reading it. Parents may be
+    /// of any namespace, so the rules are keyed by relation only.
+    tuple_to_usersets: HashMap<String, Vec<ReverseTupleToUserset>>,
+}
+
+impl ReverseRewrites {
+    fn new(namespaces: &[Namespace]) -> Self {
+        let mut rules = Self::default();
+        for namespace in namespaces {
+            for relation in &namespace.relations {
+                rules.add(&namespace.name, &relation.name, &relation.rewrite(), true);
+            }
+        }
+        rules
+    }
+
+    fn add(&mut self, namespace: &str, relation: &str, rewrite: &Rewrite, exact: bool) {
+        match rewrite {
+            Rewrite::This => {
+                *self
+                    .this
+                    .entry((namespace.to_string(), relation.to_string()))
+                    .or_default() |= exact;
+            }
+            Rewrite::ComputedUserset { relation: from } => self
+                .computed_usersets
+                .entry((namespace.to_string(), from.clone()))
+                .or_default()
+                .push((relation.to_string(), exact)),
+            Rewrite::TupleToUserset {
+                tupleset,
+                computed_userset,
+            } => self
+                .tuple_to_usersets
+                .entry(computed_userset.clone())
+                .or_default()
+                .push(ReverseTupleToUserset {
+                    namespace: namespace.to_string(),
+                    tupleset: tupleset.clone(),
+                    relation: relation.to_string(),
+                    exact,
+                }),
+            Rewrite::Union { children } => {
+                for child in children {
+                    self.add(namespace, relation, child, exact);
+                }
+            }
+            Rewrite::Intersection { children } => {
+                for child in children {
+                    self.add(namespace, relation, child, false);
+                }
+            }
+            // Subtracted subjects are never granted anything by it.
+            Rewrite::Exclusion { base, .. } => self.add(namespace, relation, base, false),
+        }
+    }
+
+    /// The relation a stored tuple grants, if its relation counts stored
+    /// subjects.
+    fn stored(&self, tuple: &RelationTuple) -> Option<(SubjectSet, bool)> {
+        self.this
+            .get(&(tuple.namespace.clone(), tuple.relation.clone()))
+            .map(|exact| {
+                (
+                    SubjectSet::new(
+                        tuple.namespace.clone(),
+                        tuple.object,
+                        tuple.relation.clone(),
+                    ),
+                    *exact,
+                )
+            })
+    }
+}
+
+/// Subject sets of one `list_subjects` call, keyed by their unique id.
+#[derive(Default)]
+struct SubjectResolver {
+    /// Subject sets on the current path, which count as empty when reached
+    /// again.
+    resolving: HashSet<Uuid>,
+    /// How often a cycle was cut so far. A subject set resolved while one was
+    /// cut may lack subjects, so it is not kept.
+    cycles: usize,
+    /// How often a subject set was cut off by the depth limit so far.
+    depth_cuts: usize,
+    /// Resolved subject sets, with the depth they were resolved to and whether
+    /// the depth limit cut them short.
+    resolved: HashMap<Uuid, (u32, bool, HashSet<Uuid>)>,
+}
+
+impl SubjectResolver {
+    /// Grows whenever a subject set is resolved with subjects possibly missing.
+    fn cuts(&self) -> usize {
+        self.cycles + self.depth_cuts
+    }
+}
+
+/// The number of items a page holds, validated before it is used as a length.
+fn page_limit(pagination: &TokenPagination) -> HeimdallResult<usize> {
+    let page_size = TokenPagination::checked_page_size(pagination.page_size)?;
+    Ok(page_size.unwrap_or(LOOKUP_PAGE_SIZE) as usize)
+}
+
+/// Drops the extra item fetched beyond `limit`, which only signals that
+/// another page exists, and returns the token of the next page.
+fn next_page_token(items: &mut Vec<Uuid>, limit: usize) -> String {
+    if items.len() > limit {
+        items.truncate(limit);
+        items
+            .last()
+            .map(TokenPagination::encode_next_page_token)
+            .unwrap_or_else(|| Uuid::nil().to_string())
+    } else {
+        Uuid::nil().to_string()
+    }
+}
+
+/// Answers "which objects of a namespace is subject S in relation R of?" by
+/// walking the relation graph backwards from the subject with the reverse
+/// indexes, following the namespace configuration's rewrites the other way
+/// round. Objects only reached through an intersection or an exclusion are
+/// confirmed with a check.
+///
+/// Also answers "which subject IDs are in relation R of namespace:object O?",
+/// the flat counterpart of expand, by evaluating the relation's rewrite as set
+/// operations over the subjects of every nested subject set.
+#[derive(Clone)]
+#[allow(unused)]
+pub struct LookupEngine {
+    services: Services,
+    check_engine: CheckEngine,
+    max_depth: u32,
+}
+
+#[allow(unused)]
+impl LookupEngine {
+    pub fn new(services: Services, check_engine: CheckEngine, max_depth: u32) -> Self {
+        Self {
+            services,
+            check_engine,
+            max_depth,
+        }
+    }
+
+    /// Lists the objects of `namespace` whose `relation` contains `subject`,
+    /// ordered by object and paged after `pagination.last_id`. Depth is capped
+    /// like for check. Each page repeats the walk from the subject, which fails
+    /// once it reaches more than `MAX_REACHED_RELATIONS`.
+    pub async fn list_objects(
+        &self,
+        ctx: &RequestContext,
+        namespace: &str,
+        relation: &str,
+        subject: &Subject,
+        max_depth: Option<u32>,
+        pagination: &TokenPagination,
+    ) -> HeimdallResult<PaginatedResponse<Vec<Uuid>>> {
+        let span = info_span!("list_objects", namespace = %namespace, relation = %relation);
+        let _guard = span.enter();
+
+        // Fails on an unknown namespace or relation.
+        self.services
+            .namespace_service
+            .get_relation(ctx, namespace, relation)
+            .await?;
+
+        let rest_depth = match max_depth {
+            Some(depth) if depth > 0 => depth.min(self.max_depth),
+            _ => self.max_depth,
+        };
+        let limit = page_limit(pagination)?;
+
+        let reached = self.reach(ctx, subject, rest_depth).await?;
+
+        let mut candidates: Vec<(Uuid, bool)> = reached
+            .into_values()
+            .filter(|(node, _)| node.namespace == namespace && node.relation == relation)
+            .map(|(node, exact)| (node.object, exact))
+            .filter(|(object, _)| pagination.last_id.is_none_or(|last_id| *object > last_id))
+            .collect();
+        candidates.sort_unstable();
+
+        let mut objects = Vec::with_capacity(candidates.len().min(limit + 1));
+        for (object, exact) in candidates {
+            if !exact {
+                let tuple = RelationTuple {
+                    namespace: namespace.to_string(),
+                    object,
+                    relation: relation.to_string(),
+                    subject: subject.clone(),
+                };
+                if !self
+                    .check_engine
+                    .check(ctx, &tuple, Some(rest_depth), false)
+                    .await?
+                    .is_allowed()
+                {
+                    trace!(object = %object, "candidate ruled out by check");
+                    continue;
+                }
+            }
+            objects.push(object);
+            if objects.len() > limit {
+                break;
+            }
+        }
+
+        let token = next_page_token(&mut objects, limit);
+        Ok(PaginatedResponse {
+            data: objects,
+            token,
+        })
+    }
+
+    /// Lists the distinct subject IDs in `subject_set`, resolving nested
+    /// subject sets and rewrites, ordered by ID and paged after
+    /// `pagination.last_id`. Subject sets already being resolved further up
+    /// count as empty, which breaks cycles. Depth is capped like for expand.
+    /// An exclusion whose subtracted branch was cut short by either lists none
+    /// of its subjects, as check denies them.
+    pub async fn list_subjects(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+        max_depth: Option<u32>,
+        pagination: &TokenPagination,
+    ) -> HeimdallResult<PaginatedResponse<Vec<Uuid>>> {
+        let span = info_span!("list_subjects", namespace = %subject_set.namespace, relation = %subject_set.relation);
+        let _guard = span.enter();
+
+        let rest_depth = match max_depth {
+            Some(depth) if depth > 0 => depth.min(self.max_depth),
+            _ => self.max_depth,
+        };
+        let limit = page_limit(pagination)?;
+
+        let mut resolver = SubjectResolver::default();
+        let subjects = self
+            .resolve_subject_set(ctx, subject_set, rest_depth, &mut resolver)
+            .await?;
+
+        let mut subjects: Vec<Uuid> = subjects
+            .into_iter()
+            .filter(|id| pagination.last_id.is_none_or(|last_id| *id > last_id))
+            .collect();
+        subjects.sort_unstable();
+        subjects.truncate(limit + 1);
+
+        let token = next_page_token(&mut subjects, limit);
+        Ok(PaginatedResponse {
+            data: subjects,
+            token,
+        })
+    }
+
+    async fn resolve_subject_set(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+        rest_depth: u32,
+        resolver: &mut SubjectResolver,
+    ) -> HeimdallResult<HashSet<Uuid>> {
+        let id = subject_set.unique_id();
+        if rest_depth == 0 {
+            resolver.depth_cuts += 1;
+            return Ok(HashSet::new());
+        }
+        if resolver.resolving.contains(&id) {
+            trace!(subject_set = %subject_set, "cutting cycle");
+            resolver.cycles += 1;
+            return Ok(HashSet::new());
+        }
+        if let Some((depth, truncated, subjects)) = resolver.resolved.get(&id)
+            && *depth >= rest_depth
+        {
+            if *truncated {
+                resolver.depth_cuts += 1;
+            }
+            return Ok(subjects.clone());
+        }
+
+        let relation = self
+            .services
+            .namespace_service
+            .get_relation(ctx, &subject_set.namespace, &subject_set.relation)
+            .await?;
+
+        let cycles = resolver.cycles;
+        let depth_cuts = resolver.depth_cuts;
+        resolver.resolving.insert(id);
+        let subjects = self
+            .resolve_rewrite(ctx, subject_set, &relation.rewrite(), rest_depth, resolver)
+            .await;
+        resolver.resolving.remove(&id);
+
+        let subjects = subjects?;
+        if resolver.cycles == cycles {
+            let truncated = resolver.depth_cuts > depth_cuts;
+            resolver
+                .resolved
+                .insert(id, (rest_depth, truncated, subjects.clone()));
+        }
+        Ok(subjects)
+    }
+
+    async fn resolve_rewrite(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+        rewrite: &Rewrite,
+        rest_depth: u32,
+        resolver: &mut SubjectResolver,
+    ) -> HeimdallResult<HashSet<Uuid>> {
+        let traversal_service = &self.services.traversal_service;
+
+        match rewrite {
+            Rewrite::This => {
+                let mut subjects = HashSet::new();
+                for stored in traversal_service
+                    .traverse_subject_set_members(ctx, subject_set)
+                    .await?
+                {
+                    match stored {
+                        Subject::Direct(subject_id) => {
+                            subjects.insert(subject_id.id);
+                        }
+                        // An empty relation names an object, not its subjects.
+                        Subject::Set(nested) if !nested.relation.is_empty() => {
+                            subjects.extend(
+                                Box::pin(self.resolve_subject_set(
+                                    ctx,
+                                    &nested,
+                                    rest_depth - 1,
+                                    resolver,
+                                ))
+                                .await?,
+                            );
+                        }
+                        Subject::Set(_) => {}
+                    }
+                }
+                Ok(subjects)
+            }
+            Rewrite::ComputedUserset { relation } => {
+                let computed = SubjectSet::new(
+                    subject_set.namespace.clone(),
+                    subject_set.object,
+                    relation.clone(),
+                );
+                Box::pin(self.resolve_subject_set(ctx, &computed, rest_depth - 1, resolver)).await
+            }
+            Rewrite::TupleToUserset {
+                tupleset,
+                computed_userset,
+            } => {
+                let tupleset = SubjectSet::new(
+                    subject_set.namespace.clone(),
+                    subject_set.object,
+                    tupleset.clone(),
+                );
+                let mut subjects = HashSet::new();
+                for stored in traversal_service
+                    .traverse_subject_set_members(ctx, &tupleset)
+                    .await?
+                {
+                    if let Subject::Set(parent) = stored {
+                        let computed = SubjectSet::new(
+                            parent.namespace,
+                            parent.object,
+                            computed_userset.clone(),
+                        );
+                        subjects.extend(
+                            Box::pin(self.resolve_subject_set(
+                                ctx,
+                                &computed,
+                                rest_depth - 1,
+                                resolver,
+                            ))
+                            .await?,
+                        );
+                    }
+                }
+                Ok(subjects)
+            }
+            Rewrite::Union { children } => {
+                let mut subjects = HashSet::new();
+                for child in children {
+                    subjects.extend(
+                        Box::pin(self.resolve_rewrite(
+                            ctx,
+                            subject_set,
+                            child,
+                            rest_depth,
+                            resolver,
+                        ))
+                        .await?,
+                    );
+                }
+                Ok(subjects)
+            }
+            Rewrite::Intersection { children } => {
+                let mut subjects: Option<HashSet<Uuid>> = None;
+                for child in children {
+                    let child = Box::pin(self.resolve_rewrite(
+                        ctx,
+                        subject_set,
+                        child,
+                        rest_depth,
+                        resolver,
+                    ))
+                    .await?;
+                    subjects = Some(match subjects {
+                        Some(subjects) => subjects.intersection(&child).copied().collect(),
+                        None => child,
+                    });
+                }
+                Ok(subjects.unwrap_or_default())
+            }
+            Rewrite::Exclusion { base, subtract } => {
+                let base =
+                    Box::pin(self.resolve_rewrite(ctx, subject_set, base, rest_depth, resolver))
+                        .await?;
+                let cuts = resolver.cuts();
+                let subtract = Box::pin(self.resolve_rewrite(
+                    ctx,
+                    subject_set,
+                    subtract,
+                    rest_depth,
+                    resolver,
+                ))
+                .await?;
+                // A subtracted branch cut short may hold any of the base's
+                // subjects, so like check none of them are granted.
+                if resolver.cuts() > cuts {
+                    trace!(subject_set = %subject_set, "subtracted subjects cut short");
+                    return Ok(HashSet::new());
+                }
+                Ok(base.difference(&subtract).copied().collect())
+            }
+        }
+    }
+
+    /// Every subject set `subject` is a member of, keyed by its unique id and
+    /// marked exact when some path to it needs no check.
+    async fn reach(
+        &self,
+        ctx: &RequestContext,
+        subject: &Subject,
+        rest_depth: u32,
+    ) -> HeimdallResult<HashMap<Uuid, (SubjectSet, bool)>> {
+        let traversal_service = &self.services.traversal_service;
+        let rules =
+            ReverseRewrites::new(&self.services.namespace_service.list_namespaces(ctx).await?);
+
+        let mut frontier = Vec::new();
+        for tuple in traversal_service.traverse_reverse(ctx, subject).await? {
+            // A subject set only matches tuples naming its relation.
+            if let (Subject::Set(start), Subject::Set(stored)) = (subject, &tuple.subject)
+                && start.relation != stored.relation
+            {
+                continue;
+            }
+            frontier.extend(rules.stored(&tuple));
+        }
+
+        let mut reached: HashMap<Uuid, (SubjectSet, bool)> = HashMap::new();
+        let mut referencing: HashMap<Uuid, Vec<RelationTuple>> = HashMap::new();
+
+        // Check finds stored tuples one level below the last relation it
+        // walks, so the walk back takes one more step.
+        for depth in 0..=rest_depth {
+            let unseen: HashSet<Uuid> = frontier
+                .iter()
+                .map(|(node, _)| node.unique_id())
+                .filter(|id| !reached.contains_key(id))
+                .collect();
+            if reached.len() + unseen.len() > MAX_REACHED_RELATIONS {
+                return Err(HeimdallError::LookupTooLarge {
+                    max: MAX_REACHED_RELATIONS,
+                });
+            }
+
+            let mut next = Vec::new();
+            for (node, exact) in frontier {
+                let id = node.unique_id();
+                if let Some((_, known)) = reached.get(&id)
+                    && (*known || !exact)
+                {
+                    continue;
+                }
+                reached.insert(id, (node.clone(), exact));
+                if depth == rest_depth {
+                    continue;
+                }
+
+                for (relation, rule_exact) in rules
+                    .computed_usersets
+                    .get(&(node.namespace.clone(), node.relation.clone()))
+                    .into_iter()
+                    .flatten()
+                {
+                    next.push((
+                        SubjectSet::new(node.namespace.clone(), node.object, relation.clone()),
+                        exact && *rule_exact,
+                    ));
+                }
+
+                let object = SubjectSet::new(node.namespace.clone(), node.object, String::new());
+                let tuples = match referencing.entry(object.unique_id()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(
+                        traversal_service
+                            .traverse_reverse(ctx, &Subject::Set(object))
+                            .await?,
+                    ),
+                };
+
+                for tuple in tuples.iter() {
+                    let Subject::Set(ref stored) = tuple.subject else {
+                        continue;
+                    };
+                    if stored.relation == node.relation
+                        && let Some((granted, rule_exact)) = rules.stored(tuple)
+                    {
+                        next.push((granted, exact && rule_exact));
+                    }
+                    for rule in rules
+                        .tuple_to_usersets
+                        .get(&node.relation)
+                        .into_iter()
+                        .flatten()
+                        .filter(|rule| {
+                            rule.namespace == tuple.namespace && rule.tupleset == tuple.relation
+                        })
+                    {
+                        next.push((
+                            SubjectSet::new(
+                                tuple.namespace.clone(),
+                                tuple.object,
+                                rule.relation.clone(),
+                            ),
+                            exact && rule.exact,
+                        ));
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        Ok(reached)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::relation_tuple::SubjectID,
+        services::memory::{context, namespaces, services, subject_set, uuid},
+    };
+
+    use super::*;
+
+    const NAMESPACES: &str = r#"
+namespaces:
+  - name: user
+  - name: group
+    relations:
+      - name: member
+  - name: document
+    relations:
+      - name: owner
+      - name: banned
+      - name: viewer
+        rewrite:
+          type: exclusion
+          base:
+            type: computed_userset
+            relation: owner
+          subtract:
+            type: computed_userset
+            relation: banned
+"#;
+
+    fn engine(tuples: &[&str]) -> LookupEngine {
+        let services = services(NAMESPACES, tuples);
+        let check_engine = CheckEngine::new(services.clone(), 5, 10);
+        LookupEngine::new(services, check_engine, 5)
+    }
+
+    async fn list_subjects(engine: &LookupEngine, max_depth: u32) -> Vec<Uuid> {
+        let pagination = TokenPagination {
+            last_id: None,
+            page_size: None,
+        };
+        engine
+            .list_subjects(
+                &context(),
+                &subject_set("document:d#viewer"),
+                Some(max_depth),
+                &pagination,
+            )
+            .await
+            .unwrap()
+            .data
+    }
+
+    #[tokio::test]
+    async fn exclusions_cut_short_list_no_subjects() {
+        let engine = engine(&[
+            "document:d#owner@alice",
+            "document:d#owner@bob",
+            "document:d#banned@group:g#member",
+            "group:g#member@alice",
+        ]);
+        assert_eq!(list_subjects(&engine, 3).await, vec![uuid("bob")]);
+        assert!(list_subjects(&engine, 2).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_objects_bounds_the_walk() {
+        let owned = |count: usize| -> Vec<String> {
+            (0..count)
+                .map(|i| format!("document:d{i}#owner@alice"))
+                .collect()
+        };
+        let list_objects = |tuples: Vec<String>| async move {
+            let tuples: Vec<&str> = tuples.iter().map(String::as_str).collect();
+            let pagination = TokenPagination {
+                last_id: None,
+                page_size: None,
+            };
+            engine(&tuples)
+                .list_objects(
+                    &context(),
+                    "document",
+                    "owner",
+                    &Subject::Direct(SubjectID::new(uuid("alice"))),
+                    None,
+                    &pagination,
+                )
+                .await
+        };
+
+        let mut objects = list_objects(owned(2)).await.unwrap().data;
+        objects.sort_unstable();
+        let mut expected = vec![uuid("d0"), uuid("d1")];
+        expected.sort_unstable();
+        assert_eq!(objects, expected);
+
+        assert!(matches!(
+            list_objects(owned(MAX_REACHED_RELATIONS + 1)).await,
+            Err(HeimdallError::LookupTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn turns_rewrites_around() {
+        let rules = ReverseRewrites::new(&namespaces(
+            r#"
+namespaces:
+  - name: document
+    relations:
+      - name: owner
+      - name: parent
+      - name: approved
+      - name: viewer
+        rewrite:
+          type: union
+          children:
+            - type: this
+            - type: computed_userset
+              relation: owner
+            - type: tuple_to_userset
+              tupleset: parent
+              computed_userset: viewer
+      - name: reviewer
+        rewrite:
+          type: intersection
+          children:
+            - type: this
+            - type: computed_userset
+              relation: approved
+      - name: commenter
+        rewrite:
+          type: exclusion
+          base:
+            type: computed_userset
+            relation: viewer
+          subtract:
+            type: computed_userset
+            relation: owner
+"#,
+        ));
+        let key = |relation: &str| ("document".to_string(), relation.to_string());
+
+        assert_eq!(rules.this.get(&key("owner")), Some(&true));
+        assert_eq!(rules.this.get(&key("viewer")), Some(&true));
+        assert_eq!(rules.this.get(&key("reviewer")), Some(&false));
+        assert_eq!(rules.this.get(&key("commenter")), None);
+
+        // `owner` is only subtracted from `commenter`, which grants it nothing.
+        assert_eq!(
+            rules.computed_usersets.get(&key("owner")),
+            Some(&vec![("viewer".to_string(), true)])
+        );
+        assert_eq!(
+            rules.computed_usersets.get(&key("approved")),
+            Some(&vec![("reviewer".to_string(), false)])
+        );
+        // The base of an exclusion still needs a check.
+        assert_eq!(
+            rules.computed_usersets.get(&key("viewer")),
+            Some(&vec![("commenter".to_string(), false)])
+        );
+
+        let tuple_to_usersets = &rules.tuple_to_usersets["viewer"];
+        assert_eq!(tuple_to_usersets.len(), 1);
+        assert_eq!(tuple_to_usersets[0].namespace, "document");
+        assert_eq!(tuple_to_usersets[0].tupleset, "parent");
+        assert_eq!(tuple_to_usersets[0].relation, "viewer");
+        assert!(tuple_to_usersets[0].exact);
+    }
+}
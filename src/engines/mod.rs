@@ -1,2 +1,4 @@
 pub mod check;
 pub mod expand;
+pub mod lookup;
+pub mod watch;
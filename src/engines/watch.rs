@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{Instrument, info_span, trace};
+
+use crate::{
+    context::RequestContext, error::HeimdallResult, models::change::RelationTupleChange,
+    services::Services,
+};
+
+pub const DEFAULT_POLL_INTERVAL_MS: u64 = 500;
+
+/// Changes read from the changelog per query.
+const WATCH_BATCH_SIZE: i64 = 500;
+
+/// Changes buffered per watcher before polling waits for it to catch up.
+const WATCH_BUFFER_SIZE: usize = 1000;
+
+/// Streams a network's relation tuple changes by polling the changelog. Every
+/// watcher polls on its own, starting after a changelog id, and stops once
+/// its stream is dropped.
+#[derive(Clone)]
+pub struct WatchEngine {
+    services: Services,
+    poll_interval: Duration,
+}
+
+impl WatchEngine {
+    pub fn new(services: Services, poll_interval: Duration) -> Self {
+        Self {
+            services,
+            poll_interval,
+        }
+    }
+
+    /// Streams the network's changes after `cursor`, as returned by
+    /// `ChangelogManager::change_cursor`, oldest first. A failed poll is sent
+    /// as the last item.
+    pub fn watch(
+        &self,
+        ctx: RequestContext,
+        mut cursor: i64,
+    ) -> ReceiverStream<HeimdallResult<RelationTupleChange>> {
+        let (tx, rx) = mpsc::channel(WATCH_BUFFER_SIZE);
+        let changelog_service = self.services.changelog_service.clone();
+        let poll_interval = self.poll_interval;
+
+        let span = info_span!("watch", network_id = %ctx.network_id(), cursor);
+
+        let poll = async move {
+            loop {
+                let changes = match changelog_service
+                    .list_changes(&ctx, cursor, WATCH_BATCH_SIZE)
+                    .await
+                {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                };
+
+                // A full batch means more changes are probably waiting.
+                let caught_up = changes.len() < WATCH_BATCH_SIZE as usize;
+                for change in changes {
+                    cursor = change.id;
+                    if tx.send(Ok(change)).await.is_err() {
+                        trace!("watcher went away");
+                        return;
+                    }
+                }
+
+                if caught_up {
+                    tokio::select! {
+                        _ = tokio::time::sleep(poll_interval) => {}
+                        _ = tx.closed() => return,
+                    }
+                }
+            }
+        };
+        tokio::spawn(poll.instrument(span));
+
+        ReceiverStream::new(rx)
+    }
+}
@@ -1,9 +1,32 @@
+use crate::models::snapshot::SnapshotToken;
+
 #[derive(Debug)]
 #[allow(unused)]
 pub enum HeimdallError {
     NilSubjectError,
     MalformedInput,
     Database(sqlx::Error),
+    Migration(sqlx::migrate::MigrateError),
+    PendingMigrations(Vec<i64>),
+    Configuration(config::ConfigError),
+    InvalidNamespaceConfig(String),
+    NamespaceNotFound(String),
+    NetworkMissing,
+    Unauthenticated,
+    PermissionDenied(String),
+    ApiKeyNotFound(uuid::Uuid),
+    NetworkNotFound(uuid::Uuid),
+    NetworkAlreadyExists(uuid::Uuid),
+    RelationNotFound { namespace: String, relation: String },
+    InvalidRelationTuple { tuple: String, reason: String },
+    BatchTooLarge { size: usize, max: usize },
+    LookupTooLarge { max: usize },
+    SnapshotExpired(SnapshotToken),
+    InvalidSettings(String),
+    Parse { input: String, reason: String },
+    Io(std::io::Error),
+    Transport(tonic::transport::Error),
+    Remote(tonic::Status),
 }
 
 impl std::fmt::Display for HeimdallError {
@@ -12,6 +35,55 @@ impl std::fmt::Display for HeimdallError {
             HeimdallError::NilSubjectError => writeln!(f, "Subject missing"),
             HeimdallError::MalformedInput => writeln!(f, "Malformed Input"),
             HeimdallError::Database(e) => writeln!(f, "Database Error: {e}"),
+            HeimdallError::Migration(e) => writeln!(f, "Migration Error: {e}"),
+            HeimdallError::PendingMigrations(versions) => {
+                let versions: Vec<String> = versions.iter().map(i64::to_string).collect();
+                writeln!(
+                    f,
+                    "Database schema is behind, pending migrations: {}",
+                    versions.join(", ")
+                )
+            }
+            HeimdallError::Configuration(e) => writeln!(f, "Configuration Error: {e}"),
+            HeimdallError::InvalidNamespaceConfig(reason) => {
+                writeln!(f, "Invalid Namespace Configuration: {reason}")
+            }
+            HeimdallError::NamespaceNotFound(namespace) => {
+                writeln!(f, "Namespace not found: {namespace}")
+            }
+            HeimdallError::Unauthenticated => writeln!(f, "Missing or invalid API key"),
+            HeimdallError::PermissionDenied(reason) => writeln!(f, "Permission denied: {reason}"),
+            HeimdallError::ApiKeyNotFound(id) => writeln!(f, "API key not found: {id}"),
+            HeimdallError::NetworkMissing => writeln!(f, "Network id missing or malformed"),
+            HeimdallError::NetworkNotFound(id) => writeln!(f, "Network not found: {id}"),
+            HeimdallError::NetworkAlreadyExists(id) => {
+                writeln!(f, "Network already exists: {id}")
+            }
+            HeimdallError::RelationNotFound {
+                namespace,
+                relation,
+            } => writeln!(f, "Relation not found: {namespace}#{relation}"),
+            HeimdallError::InvalidRelationTuple { tuple, reason } => {
+                writeln!(f, "Invalid relation tuple {tuple}: {reason}")
+            }
+            HeimdallError::BatchTooLarge { size, max } => {
+                writeln!(f, "Batch of {size} items exceeds the maximum of {max}")
+            }
+            HeimdallError::LookupTooLarge { max } => writeln!(
+                f,
+                "Lookup reaches more than {max} relations, lower its max depth"
+            ),
+            HeimdallError::SnapshotExpired(token) => writeln!(
+                f,
+                "Snapshot {token} predates a delete and can no longer be read exactly"
+            ),
+            HeimdallError::Parse { input, reason } => writeln!(f, "Cannot parse {input}: {reason}"),
+            HeimdallError::InvalidSettings(reason) => writeln!(f, "Invalid Settings: {reason}"),
+            HeimdallError::Io(e) => writeln!(f, "IO Error: {e}"),
+            HeimdallError::Transport(e) => writeln!(f, "Transport Error: {e}"),
+            HeimdallError::Remote(status) => {
+                writeln!(f, "Remote Error: {:?}: {}", status.code(), status.message())
+            }
         }
     }
 }
@@ -25,3 +97,33 @@ impl From<sqlx::Error> for HeimdallError {
         HeimdallError::Database(value)
     }
 }
+
+impl From<sqlx::migrate::MigrateError> for HeimdallError {
+    fn from(value: sqlx::migrate::MigrateError) -> Self {
+        HeimdallError::Migration(value)
+    }
+}
+
+impl From<config::ConfigError> for HeimdallError {
+    fn from(value: config::ConfigError) -> Self {
+        HeimdallError::Configuration(value)
+    }
+}
+
+impl From<std::io::Error> for HeimdallError {
+    fn from(value: std::io::Error) -> Self {
+        HeimdallError::Io(value)
+    }
+}
+
+impl From<tonic::transport::Error> for HeimdallError {
+    fn from(value: tonic::transport::Error) -> Self {
+        HeimdallError::Transport(value)
+    }
+}
+
+impl From<tonic::Status> for HeimdallError {
+    fn from(value: tonic::Status) -> Self {
+        HeimdallError::Remote(value)
+    }
+}
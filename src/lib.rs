@@ -1,4 +1,4 @@
-mod api;
+pub mod api;
 mod context;
 mod engines;
 mod error;
@@ -6,3 +6,4 @@ mod middlewares;
 mod models;
 mod persistance;
 mod services;
+mod settings;
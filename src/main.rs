@@ -1,3 +1,6 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    justid_heimdall::api::cmd::run().await
 }
@@ -0,0 +1,238 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{Method, header::AUTHORIZATION};
+use uuid::Uuid;
+
+use crate::{
+    error::{HeimdallError, HeimdallResult},
+    models::api_key::{ApiKeyScope, hash_api_key},
+    services::traits::ApiKeyManager,
+};
+
+use super::RequestMetadata;
+
+/// What a request needs from its API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Scope(ApiKeyScope),
+    /// Managing networks spans tenants, so only root keys may do it.
+    Root,
+}
+
+/// Which router a request came through. It decides how the request path is
+/// read, so it must never be taken from the request itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Http,
+    Grpc,
+}
+
+/// Validates bearer API keys and binds the key's network to the request.
+pub struct Authenticator {
+    enabled: bool,
+    root_key_hashes: HashSet<String>,
+    api_key_service: Arc<dyn ApiKeyManager>,
+}
+
+impl Authenticator {
+    pub fn new(
+        enabled: bool,
+        root_key_hashes: Vec<String>,
+        api_key_service: Arc<dyn ApiKeyManager>,
+    ) -> Self {
+        Self {
+            enabled,
+            root_key_hashes: root_key_hashes
+                .into_iter()
+                .map(|hash| hash.to_ascii_lowercase())
+                .collect(),
+            api_key_service,
+        }
+    }
+
+    /// Root keys act on whatever network the request names. Network keys
+    /// replace it with their own, and are refused when the request names a
+    /// different one.
+    pub async fn authorize<B>(
+        &self,
+        request: &mut http::Request<B>,
+        protocol: Protocol,
+    ) -> HeimdallResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let secret = bearer_token(request).ok_or(HeimdallError::Unauthenticated)?;
+        if self.root_key_hashes.contains(&hash_api_key(secret)) {
+            return Ok(());
+        }
+
+        let api_key = self
+            .api_key_service
+            .authenticate(secret)
+            .await?
+            .ok_or(HeimdallError::Unauthenticated)?;
+
+        match required_access(protocol, request.method(), request.uri().path()) {
+            Access::Root => {
+                return Err(HeimdallError::PermissionDenied(
+                    "managing networks requires a root key".to_string(),
+                ));
+            }
+            Access::Scope(scope) if api_key.scope < scope => {
+                return Err(HeimdallError::PermissionDenied(format!(
+                    "API key scope {} does not allow {scope} requests",
+                    api_key.scope
+                )));
+            }
+            Access::Scope(_) => {}
+        }
+
+        let metadata = request
+            .extensions_mut()
+            .get_mut::<RequestMetadata>()
+            .ok_or(HeimdallError::NetworkMissing)?;
+        bind_network(metadata, api_key.network_id)
+    }
+}
+
+fn bind_network(metadata: &mut RequestMetadata, network_id: Uuid) -> HeimdallResult<()> {
+    match metadata.network_id {
+        Some(requested) if requested != network_id => Err(HeimdallError::PermissionDenied(
+            "API key belongs to another network".to_string(),
+        )),
+        _ => {
+            metadata.network_id = Some(network_id);
+            Ok(())
+        }
+    }
+}
+
+fn bearer_token<B>(request: &http::Request<B>) -> Option<&str> {
+    let value = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Maps a route to the access it needs. gRPC methods are grouped by service
+/// and unknown services need a root key. HTTP routes are grouped by path and
+/// method; the check endpoints only read even when posted to.
+fn required_access(protocol: Protocol, method: &Method, path: &str) -> Access {
+    if protocol == Protocol::Grpc {
+        return match path.split('/').nth(1).unwrap_or_default() {
+            "heimdall.v1.ReadService"
+            | "heimdall.v1.CheckService"
+            | "heimdall.v1.ExpandService"
+            | "heimdall.v1.LookupService"
+            | "heimdall.v1.WatchService" => Access::Scope(ApiKeyScope::ReadOnly),
+            "heimdall.v1.WriteService" => Access::Scope(ApiKeyScope::Write),
+            "heimdall.v1.ApiKeyService" => Access::Scope(ApiKeyScope::Admin),
+            _ => Access::Root,
+        };
+    }
+
+    if path.starts_with("/networks") {
+        Access::Root
+    } else if path.starts_with("/api-keys") {
+        Access::Scope(ApiKeyScope::Admin)
+    } else if method == Method::GET
+        || method == Method::HEAD
+        || path == "/relation-tuples/check"
+        || path == "/relation-tuples/check/batch"
+    {
+        Access::Scope(ApiKeyScope::ReadOnly)
+    } else {
+        Access::Scope(ApiKeyScope::Write)
+    }
+}
+
+/// Axum middleware running `Authenticator::authorize`. Each router mounts it
+/// with its own protocol, and gRPC rejections are answered as a gRPC status.
+pub async fn authenticate(
+    State((authenticator, protocol)): State<(Arc<Authenticator>, Protocol)>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match authenticator.authorize(&mut request, protocol).await {
+        Ok(()) => next.run(request).await,
+        Err(e) => match protocol {
+            Protocol::Http => e.into_response(),
+            Protocol::Grpc => tonic::Status::from(e).into_http(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(method: Method, path: &str) -> Access {
+        required_access(Protocol::Http, &method, path)
+    }
+
+    fn grpc(path: &str) -> Access {
+        required_access(Protocol::Grpc, &Method::POST, path)
+    }
+
+    #[test]
+    fn http_routes_need_their_scope() {
+        let read = Access::Scope(ApiKeyScope::ReadOnly);
+        let write = Access::Scope(ApiKeyScope::Write);
+        let admin = Access::Scope(ApiKeyScope::Admin);
+
+        assert_eq!(http(Method::GET, "/relation-tuples"), read);
+        assert_eq!(http(Method::HEAD, "/relation-tuples"), read);
+        assert_eq!(http(Method::POST, "/relation-tuples/check"), read);
+        assert_eq!(http(Method::POST, "/relation-tuples/check/batch"), read);
+        assert_eq!(http(Method::PUT, "/relation-tuples"), write);
+        assert_eq!(http(Method::PATCH, "/relation-tuples"), write);
+        assert_eq!(http(Method::DELETE, "/relation-tuples"), write);
+        assert_eq!(http(Method::GET, "/api-keys"), admin);
+        assert_eq!(http(Method::DELETE, "/api-keys/1"), admin);
+        assert_eq!(http(Method::GET, "/networks"), Access::Root);
+        assert_eq!(http(Method::DELETE, "/networks/1"), Access::Root);
+    }
+
+    #[test]
+    fn grpc_services_need_their_scope() {
+        let read = Access::Scope(ApiKeyScope::ReadOnly);
+
+        assert_eq!(grpc("/heimdall.v1.ReadService/ListRelationTuples"), read);
+        assert_eq!(grpc("/heimdall.v1.CheckService/CheckBatch"), read);
+        assert_eq!(grpc("/heimdall.v1.ExpandService/Expand"), read);
+        assert_eq!(grpc("/heimdall.v1.LookupService/ListObjects"), read);
+        assert_eq!(grpc("/heimdall.v1.WatchService/Watch"), read);
+        assert_eq!(
+            grpc("/heimdall.v1.WriteService/TransactRelationTuples"),
+            Access::Scope(ApiKeyScope::Write)
+        );
+        assert_eq!(
+            grpc("/heimdall.v1.ApiKeyService/DeleteApiKey"),
+            Access::Scope(ApiKeyScope::Admin)
+        );
+        assert_eq!(
+            grpc("/heimdall.v1.NetworkService/DeleteNetwork"),
+            Access::Root
+        );
+        assert_eq!(grpc("/unknown.Service/Method"), Access::Root);
+    }
+
+    #[test]
+    fn http_paths_are_not_read_as_grpc_services() {
+        // Only the router decides the protocol, so an HTTP route named like a
+        // gRPC service still gets the HTTP rules and vice versa.
+        assert_eq!(http(Method::DELETE, "/networks/1"), Access::Root);
+        assert_eq!(
+            http(Method::POST, "/heimdall.v1.NetworkService/DeleteNetwork"),
+            Access::Scope(ApiKeyScope::Write)
+        );
+        assert_eq!(grpc("/networks/1"), Access::Root);
+        assert_eq!(grpc("/relation-tuples"), Access::Root);
+    }
+}
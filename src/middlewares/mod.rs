@@ -1 +1,5 @@
+mod auth;
+mod request_context;
 
+pub use self::auth::{Authenticator, Protocol, authenticate};
+pub use self::request_context::{NetworkResolver, RequestContextLayer, RequestMetadata};
@@ -0,0 +1,258 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http::{HeaderValue, Request, Response, header::HOST};
+use tower::{Layer, Service};
+use tracing::{Instrument, info_span};
+use uuid::Uuid;
+
+use crate::{
+    context::{NETWORK_ID_HEADER, REQUEST_ID_HEADER, RequestContext},
+    error::{HeimdallError, HeimdallResult},
+};
+
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Longest caller-supplied request id that is propagated as-is.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Everything the middleware resolved about a request, stored in the request
+/// extensions for the HTTP and gRPC handlers.
+#[derive(Debug, Clone)]
+pub struct RequestMetadata {
+    pub network_id: Option<Uuid>,
+    pub request_id: String,
+    pub trace_id: String,
+}
+
+impl RequestMetadata {
+    /// Fails when no network could be resolved, since every service call is
+    /// scoped to one.
+    pub fn context(&self) -> HeimdallResult<RequestContext> {
+        let network_id = self.network_id.ok_or(HeimdallError::NetworkMissing)?;
+        Ok(RequestContext::new(
+            network_id,
+            self.request_id.clone(),
+            self.trace_id.clone(),
+        ))
+    }
+}
+
+/// Resolves the network of a request from the network id header, or else from
+/// the configured host mapping.
+#[derive(Debug)]
+pub struct NetworkResolver {
+    hosts: HashMap<String, Uuid>,
+}
+
+impl NetworkResolver {
+    pub fn new(hosts: HashMap<String, Uuid>) -> Self {
+        let hosts = hosts
+            .into_iter()
+            .map(|(host, network_id)| (host.to_ascii_lowercase(), network_id))
+            .collect();
+        Self { hosts }
+    }
+
+    /// A malformed header resolves to no network rather than falling back to
+    /// the host, so a typo cannot land a request in another network.
+    pub fn resolve<B>(&self, request: &Request<B>) -> Option<Uuid> {
+        if let Some(value) = request.headers().get(NETWORK_ID_HEADER) {
+            return value
+                .to_str()
+                .ok()
+                .and_then(|value| Uuid::parse_str(value).ok());
+        }
+
+        host(request).and_then(|host| self.hosts.get(&host).copied())
+    }
+}
+
+/// HTTP/1 requests name the host in the `Host` header, HTTP/2 (and so gRPC)
+/// in the `:authority` pseudo-header, which ends up in the URI.
+fn host<B>(request: &Request<B>) -> Option<String> {
+    let host = match request.uri().authority() {
+        Some(authority) => authority.host().to_string(),
+        None => {
+            let value = request.headers().get(HOST)?.to_str().ok()?;
+            value
+                .parse::<http::uri::Authority>()
+                .ok()?
+                .host()
+                .to_string()
+        }
+    };
+    Some(host.to_ascii_lowercase())
+}
+
+fn request_id<B>(request: &Request<B>) -> String {
+    request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| {
+            !value.is_empty()
+                && value.len() <= MAX_REQUEST_ID_LENGTH
+                && value.bytes().all(|b| b.is_ascii_graphic())
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Picks the trace id out of a W3C `traceparent` header, formatted as
+/// `version-traceid-parentid-flags`, or starts a new trace.
+fn trace_id<B>(request: &Request<B>) -> String {
+    request
+        .headers()
+        .get(TRACEPARENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_traceparent)
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string())
+}
+
+fn parse_traceparent(value: &str) -> Option<String> {
+    let is_hex = |part: &str, len: usize| {
+        part.len() == len
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    };
+    let is_zero = |part: &str| part.bytes().all(|b| b == b'0');
+
+    let mut parts = value.trim().split('-');
+    let (version, trace_id, parent_id, flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+
+    // Version 00 has exactly four fields, later versions may append more.
+    if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) {
+        return None;
+    }
+    if !is_hex(trace_id, 32) || is_zero(trace_id) {
+        return None;
+    }
+    if !is_hex(parent_id, 16) || is_zero(parent_id) || !is_hex(flags, 2) {
+        return None;
+    }
+
+    Some(trace_id.to_string())
+}
+
+/// Attaches `RequestMetadata` to every request and runs it inside a span
+/// carrying its ids. Works for both the axum router and the tonic server, as
+/// both are `http::Request` services.
+#[derive(Clone)]
+pub struct RequestContextLayer {
+    resolver: Arc<NetworkResolver>,
+}
+
+impl RequestContextLayer {
+    pub fn new(resolver: NetworkResolver) -> Self {
+        Self {
+            resolver: Arc::new(resolver),
+        }
+    }
+}
+
+impl<S> Layer<S> for RequestContextLayer {
+    type Service = RequestContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestContextService {
+            inner,
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestContextService<S> {
+    inner: S,
+    resolver: Arc<NetworkResolver>,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for RequestContextService<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let metadata = RequestMetadata {
+            network_id: self.resolver.resolve(&request),
+            request_id: request_id(&request),
+            trace_id: trace_id(&request),
+        };
+
+        let span = info_span!(
+            "request",
+            request_id = %metadata.request_id,
+            trace_id = %metadata.trace_id,
+            network_id = ?metadata.network_id,
+        );
+        let request_id = HeaderValue::from_str(&metadata.request_id).ok();
+
+        request.extensions_mut().insert(metadata);
+        let response = span.in_scope(|| self.inner.call(request));
+
+        Box::pin(
+            async move {
+                let mut response = response.await?;
+                if let Some(request_id) = request_id {
+                    response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+                }
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    #[test]
+    fn reads_the_trace_id_of_a_traceparent() {
+        assert_eq!(
+            parse_traceparent(&format!("00-{TRACE_ID}-00f067aa0ba902b7-01")).as_deref(),
+            Some(TRACE_ID)
+        );
+        // Later versions may append fields.
+        assert_eq!(
+            parse_traceparent(&format!("01-{TRACE_ID}-00f067aa0ba902b7-01-extra")).as_deref(),
+            Some(TRACE_ID)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_traceparents() {
+        for value in [
+            "",
+            "00",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-1",
+        ] {
+            assert_eq!(parse_traceparent(value), None, "{value}");
+        }
+    }
+}
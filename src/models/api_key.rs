@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{error::HeimdallError, persistance::schema::ApiKey as DbApiKey};
+
+/// Prefix of every generated key, so leaked keys are easy to scan for.
+const API_KEY_PREFIX: &str = "hdl_";
+
+/// What a key may do within its network. Each scope includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Listing relation tuples, check and expand.
+    ReadOnly,
+    /// Also writing and deleting relation tuples.
+    Write,
+    /// Also managing the network's API keys.
+    Admin,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub network_id: Uuid,
+    pub name: String,
+    pub scope: ApiKeyScope,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A newly created key. The secret is only available at creation time.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub secret: String,
+}
+
+/// Returns a new random key. Two v4 UUIDs give 244 random bits.
+pub fn generate_api_key() -> String {
+    format!(
+        "{API_KEY_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// Keys are random and long, so a plain SHA-256 is enough to keep them safe at
+/// rest while still allowing a lookup by hash.
+pub fn hash_api_key(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = HeimdallError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_only" => Ok(ApiKeyScope::ReadOnly),
+            "write" => Ok(ApiKeyScope::Write),
+            "admin" => Ok(ApiKeyScope::Admin),
+            _ => Err(HeimdallError::Parse {
+                input: s.to_string(),
+                reason: "expected read_only, write or admin".to_string(),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyScope::ReadOnly => write!(f, "read_only"),
+            ApiKeyScope::Write => write!(f, "write"),
+            ApiKeyScope::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl TryFrom<DbApiKey> for ApiKey {
+    type Error = HeimdallError;
+
+    fn try_from(value: DbApiKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            network_id: value.nid,
+            name: value.name,
+            scope: value.scope.parse()?,
+            created_at: value.created_at,
+        })
+    }
+}
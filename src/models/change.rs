@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    error::HeimdallError,
+    models::{
+        relation_tuple::{MapIds, RelationTuple, Subject, SubjectID, SubjectSet},
+        snapshot::SnapshotToken,
+    },
+    persistance::schema::RelationTupleChange as DbRelationTupleChange,
+};
+
+/// What a change did to a tuple. Patches use the same actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Insert,
+    Delete,
+}
+
+/// A relation tuple insert or delete, as recorded in the changelog.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationTupleChange<Id = Uuid> {
+    /// Position in the changelog. Later changes of a network have larger ids.
+    pub id: i64,
+    pub action: ChangeAction,
+    pub relation_tuple: RelationTuple<Id>,
+    /// Snapshot the change was committed at.
+    pub snaptoken: SnapshotToken,
+}
+
+impl<Id> MapIds<Id> for RelationTupleChange<Id> {
+    type Mapped<T> = RelationTupleChange<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        self.relation_tuple.for_each_id(f);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        RelationTupleChange {
+            id: self.id,
+            action: self.action,
+            relation_tuple: self.relation_tuple.map_ids(f),
+            snaptoken: self.snaptoken,
+        }
+    }
+}
+
+impl std::fmt::Display for ChangeAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeAction::Insert => write!(f, "insert"),
+            ChangeAction::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+impl TryFrom<DbRelationTupleChange> for RelationTupleChange {
+    type Error = HeimdallError;
+
+    fn try_from(value: DbRelationTupleChange) -> Result<Self, Self::Error> {
+        let action = match value.action.as_str() {
+            "insert" => ChangeAction::Insert,
+            "delete" => ChangeAction::Delete,
+            _ => {
+                return Err(HeimdallError::Parse {
+                    input: value.action,
+                    reason: "expected insert or delete".to_string(),
+                });
+            }
+        };
+
+        let subject = match value.subject_id {
+            Some(id) => Subject::Direct(SubjectID { id }),
+            None => Subject::Set(SubjectSet {
+                namespace: value.subject_set_namespace.unwrap_or_default(),
+                object: value.subject_set_object.unwrap_or_default(),
+                relation: value.subject_set_relation.unwrap_or_default(),
+            }),
+        };
+
+        Ok(Self {
+            id: value.id,
+            action,
+            relation_tuple: RelationTuple {
+                namespace: value.namespace,
+                object: value.object,
+                relation: value.relation,
+                subject,
+            },
+            snaptoken: value.commit_time.into(),
+        })
+    }
+}
@@ -0,0 +1,129 @@
+#![allow(unused)]
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{
+    relation_tuple::MapIds,
+    traversal::{Traversal, TraversalResult},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Membership {
+    Allowed,
+    Denied,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckReason {
+    /// A tuple matching the request exists as-is.
+    DirectTuple,
+    /// The subject was reached by walking the relation graph.
+    Traversal(Traversal),
+    /// Every branch of an intersection matched.
+    Intersection,
+    /// The base of an exclusion matched and the subtracted branch did not.
+    Exclusion,
+    /// The subtracted branch of an exclusion matched.
+    Excluded,
+    /// The walk was cut short before a decision could be made.
+    MaxDepthReached,
+    /// Every path was explored without reaching the subject.
+    NoPathFound,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub membership: Membership,
+    pub reason: CheckReason,
+    /// The steps from the checked tuple to the match the decision rests on:
+    /// what granted an allowed check, or what excluded a denied one.
+    pub path: Vec<TraversalResult>,
+    /// Every step walked without reaching the subject, for denied checks.
+    pub tried: Vec<TraversalResult>,
+}
+
+impl CheckResult {
+    pub fn allowed(reason: CheckReason) -> Self {
+        Self {
+            membership: Membership::Allowed,
+            reason,
+            path: Vec::new(),
+            tried: Vec::new(),
+        }
+    }
+
+    pub fn denied(reason: CheckReason) -> Self {
+        Self {
+            membership: Membership::Denied,
+            reason,
+            path: Vec::new(),
+            tried: Vec::new(),
+        }
+    }
+
+    pub fn with_path(mut self, path: Vec<TraversalResult>) -> Self {
+        self.path = path;
+        self
+    }
+
+    pub fn with_tried(mut self, tried: Vec<TraversalResult>) -> Self {
+        self.tried = tried;
+        self
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        self.membership == Membership::Allowed
+    }
+
+    pub fn explanation(&self) -> CheckExplanation {
+        CheckExplanation {
+            reason: self.reason,
+            path: self.path.clone(),
+            tried: self.tried.clone(),
+        }
+    }
+}
+
+/// Why a check was decided the way it was, for clients that ask.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckExplanation<Id = Uuid> {
+    pub reason: CheckReason,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<TraversalResult<Id>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tried: Vec<TraversalResult<Id>>,
+}
+
+impl<Id> MapIds<Id> for CheckExplanation<Id> {
+    type Mapped<T> = CheckExplanation<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        self.path.for_each_id(f);
+        self.tried.for_each_id(f);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        CheckExplanation {
+            reason: self.reason,
+            path: self.path.map_ids(f),
+            tried: self.tried.map_ids(f),
+        }
+    }
+}
+
+impl std::fmt::Display for CheckReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckReason::DirectTuple => write!(f, "direct tuple"),
+            CheckReason::Traversal(via) => write!(f, "via {via}"),
+            CheckReason::Intersection => write!(f, "all intersection branches matched"),
+            CheckReason::Exclusion => write!(f, "exclusion base matched"),
+            CheckReason::Excluded => write!(f, "excluded"),
+            CheckReason::MaxDepthReached => write!(f, "max depth reached"),
+            CheckReason::NoPathFound => write!(f, "no path found"),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+#![allow(unused)]
+
+use serde::Serialize;
+
+use uuid::Uuid;
+
+use super::relation_tuple::{MapIds, Subject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeNodeType {
+    Union,
+    Leaf,
+    ComputedUserset,
+    TupleToUserset,
+    Intersection,
+    /// The first child is the base and the second is subtracted from it.
+    Exclusion,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tree<Id = Uuid> {
+    #[serde(rename = "type")]
+    pub node_type: TreeNodeType,
+    #[serde(flatten)]
+    pub subject: Subject<Id>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Tree<Id>>,
+}
+
+impl<Id> Tree<Id> {
+    /// Builds a union of `children`, or a leaf when there is nothing to join.
+    pub fn union(subject: Subject<Id>, children: Vec<Tree<Id>>) -> Self {
+        let node_type = if children.is_empty() {
+            TreeNodeType::Leaf
+        } else {
+            TreeNodeType::Union
+        };
+        Self {
+            node_type,
+            subject,
+            children,
+        }
+    }
+
+    pub fn leaf(subject: Subject<Id>) -> Self {
+        Self {
+            node_type: TreeNodeType::Leaf,
+            subject,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<Id> MapIds<Id> for Tree<Id> {
+    type Mapped<T> = Tree<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        self.subject.for_each_id(f);
+        self.children.for_each_id(f);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        Tree {
+            node_type: self.node_type,
+            subject: self.subject.map_ids(f),
+            children: self.children.map_ids(f),
+        }
+    }
+}
+
+impl std::fmt::Display for TreeNodeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeNodeType::Union => write!(f, "union"),
+            TreeNodeType::Leaf => write!(f, "leaf"),
+            TreeNodeType::ComputedUserset => write!(f, "computed userset"),
+            TreeNodeType::TupleToUserset => write!(f, "tuple to userset"),
+            TreeNodeType::Intersection => write!(f, "intersection"),
+            TreeNodeType::Exclusion => write!(f, "exclusion"),
+        }
+    }
+}
@@ -1,4 +1,11 @@
+pub mod api_key;
+pub mod change;
+pub mod check;
+pub mod expand;
+pub mod namespace;
+pub mod network;
 pub mod query;
 pub mod relation_tuple;
 pub mod response;
+pub mod snapshot;
 pub mod traversal;
@@ -0,0 +1,273 @@
+#![allow(unused)]
+
+use serde::{Deserialize, Serialize};
+
+use super::relation_tuple::SubjectSet;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Namespace {
+    pub name: String,
+    #[serde(default)]
+    pub relations: Vec<Relation>,
+}
+
+impl Namespace {
+    pub fn relation(&self, name: &str) -> Option<&Relation> {
+        self.relations.iter().find(|relation| relation.name == name)
+    }
+
+    /// Whether tuples stored on `relation` are ever read: its rewrite contains
+    /// `this`, or another relation follows it as a tuple-to-userset tupleset.
+    pub fn reads_stored(&self, relation: &Relation) -> bool {
+        relation.rewrite().contains_this()
+            || self
+                .relations
+                .iter()
+                .any(|other| other.rewrite().follows_tupleset(&relation.name))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relation {
+    pub name: String,
+    /// How the relation is computed. A relation without a rewrite only
+    /// contains the subjects stored on it, which is the same as `this`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewrite: Option<Rewrite>,
+    /// Subject sets that may be stored on the relation. Subject IDs are always
+    /// accepted, and an empty list accepts any declared subject set naming a
+    /// relation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subject_sets: Vec<SubjectSetType>,
+}
+
+/// A subject set allowed on a relation. An empty relation refers to the object
+/// itself, e.g. a document's `parent` folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubjectSetType {
+    pub namespace: String,
+    #[serde(default)]
+    pub relation: String,
+}
+
+impl Relation {
+    /// An object-only subject set, with an empty relation, has to be listed
+    /// explicitly since only tuple-to-userset parents can follow it.
+    pub fn allows_subject_set(&self, subject_set: &SubjectSet) -> bool {
+        let listed = self.subject_sets.iter().any(|allowed| {
+            allowed.namespace == subject_set.namespace && allowed.relation == subject_set.relation
+        });
+        listed || (self.subject_sets.is_empty() && !subject_set.relation.is_empty())
+    }
+
+    pub fn rewrite(&self) -> Rewrite {
+        self.rewrite.clone().unwrap_or(Rewrite::This)
+    }
+}
+
+/// Userset rewrite rules, following the Zanzibar paper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Rewrite {
+    /// Subjects stored directly on the relation, including nested subject sets.
+    This,
+    /// Subjects of another relation on the same object, e.g. `editor` implies `viewer`.
+    ComputedUserset { relation: String },
+    /// Subjects of `computed_userset` on every object stored under `tupleset`,
+    /// e.g. `viewer` of a document's `parent` folder.
+    TupleToUserset {
+        tupleset: String,
+        computed_userset: String,
+    },
+    /// Subjects of any child rewrite.
+    Union { children: Vec<Rewrite> },
+    /// Subjects of every child rewrite.
+    Intersection { children: Vec<Rewrite> },
+    /// Subjects of `base` that are not subjects of `subtract`.
+    Exclusion {
+        base: Box<Rewrite>,
+        subtract: Box<Rewrite>,
+    },
+}
+
+impl std::fmt::Display for Rewrite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rewrite::This => write!(f, "this"),
+            Rewrite::ComputedUserset { .. } => write!(f, "computed_userset"),
+            Rewrite::TupleToUserset { .. } => write!(f, "tuple_to_userset"),
+            Rewrite::Union { .. } => write!(f, "union"),
+            Rewrite::Intersection { .. } => write!(f, "intersection"),
+            Rewrite::Exclusion { .. } => write!(f, "exclusion"),
+        }
+    }
+}
+
+#[allow(unused)]
+impl Rewrite {
+    /// Whether every stored subject is a member: the rewrite is `this`, or a
+    /// union reaching `this` without passing an intersection or exclusion.
+    pub fn grants_stored(&self) -> bool {
+        match self {
+            Rewrite::This => true,
+            Rewrite::Union { children } => children.iter().any(Rewrite::grants_stored),
+            _ => false,
+        }
+    }
+
+    /// Whether `this` appears anywhere in the rewrite.
+    pub fn contains_this(&self) -> bool {
+        match self {
+            Rewrite::This => true,
+            Rewrite::Union { children } | Rewrite::Intersection { children } => {
+                children.iter().any(Rewrite::contains_this)
+            }
+            Rewrite::Exclusion { base, subtract } => {
+                base.contains_this() || subtract.contains_this()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the rewrite reads the tuples stored on `relation` as a
+    /// tuple-to-userset tupleset.
+    pub fn follows_tupleset(&self, relation: &str) -> bool {
+        match self {
+            Rewrite::TupleToUserset { tupleset, .. } => tupleset == relation,
+            Rewrite::Union { children } | Rewrite::Intersection { children } => children
+                .iter()
+                .any(|child| child.follows_tupleset(relation)),
+            Rewrite::Exclusion { base, subtract } => {
+                base.follows_tupleset(relation) || subtract.follows_tupleset(relation)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(subject_sets: Vec<SubjectSetType>) -> Relation {
+        Relation {
+            name: "parent".to_string(),
+            rewrite: None,
+            subject_sets,
+        }
+    }
+
+    fn subject_set(namespace: &str, relation: &str) -> SubjectSet {
+        SubjectSet::new(
+            namespace.to_string(),
+            Default::default(),
+            relation.to_string(),
+        )
+    }
+
+    fn computed(relation: &str) -> Rewrite {
+        Rewrite::ComputedUserset {
+            relation: relation.to_string(),
+        }
+    }
+
+    fn tuple_to_userset(tupleset: &str) -> Rewrite {
+        Rewrite::TupleToUserset {
+            tupleset: tupleset.to_string(),
+            computed_userset: "viewer".to_string(),
+        }
+    }
+
+    #[test]
+    fn stored_subjects_are_granted_through_unions_only() {
+        assert!(Rewrite::This.grants_stored());
+        assert!(
+            Rewrite::Union {
+                children: vec![computed("owner"), Rewrite::This],
+            }
+            .grants_stored()
+        );
+        assert!(!computed("owner").grants_stored());
+        assert!(
+            !Rewrite::Intersection {
+                children: vec![Rewrite::This, computed("approved")],
+            }
+            .grants_stored()
+        );
+        assert!(
+            !Rewrite::Exclusion {
+                base: Box::new(Rewrite::This),
+                subtract: Box::new(computed("banned")),
+            }
+            .grants_stored()
+        );
+    }
+
+    #[test]
+    fn this_is_found_anywhere_in_the_rewrite() {
+        assert!(
+            Rewrite::Exclusion {
+                base: Box::new(Rewrite::Intersection {
+                    children: vec![computed("approved"), Rewrite::This],
+                }),
+                subtract: Box::new(computed("banned")),
+            }
+            .contains_this()
+        );
+        assert!(
+            !Rewrite::Union {
+                children: vec![computed("owner"), tuple_to_userset("parent")],
+            }
+            .contains_this()
+        );
+    }
+
+    #[test]
+    fn tuplesets_are_found_anywhere_in_the_rewrite() {
+        let rewrite = Rewrite::Exclusion {
+            base: Box::new(Rewrite::Union {
+                children: vec![Rewrite::This, tuple_to_userset("parent")],
+            }),
+            subtract: Box::new(computed("banned")),
+        };
+        assert!(rewrite.follows_tupleset("parent"));
+        assert!(!rewrite.follows_tupleset("banned"));
+        assert!(!computed("parent").follows_tupleset("parent"));
+    }
+
+    #[test]
+    fn relations_are_read_through_this_or_as_tuplesets() {
+        let namespace = Namespace {
+            name: "document".to_string(),
+            relations: vec![
+                Relation {
+                    rewrite: Some(computed("viewer")),
+                    ..relation(Vec::new())
+                },
+                Relation {
+                    name: "viewer".to_string(),
+                    rewrite: Some(tuple_to_userset("parent")),
+                    subject_sets: Vec::new(),
+                },
+            ],
+        };
+        // `parent` is computed from `viewer` but followed as a tupleset.
+        assert!(namespace.reads_stored(&namespace.relations[0]));
+        assert!(!namespace.reads_stored(&namespace.relations[1]));
+        assert!(namespace.reads_stored(&relation(Vec::new())));
+    }
+
+    #[test]
+    fn object_only_subject_sets_must_be_listed() {
+        let unrestricted = relation(Vec::new());
+        assert!(unrestricted.allows_subject_set(&subject_set("group", "member")));
+        assert!(!unrestricted.allows_subject_set(&subject_set("folder", "")));
+
+        let parent = relation(vec![SubjectSetType {
+            namespace: "folder".to_string(),
+            relation: String::new(),
+        }]);
+        assert!(parent.allows_subject_set(&subject_set("folder", "")));
+        assert!(!parent.allows_subject_set(&subject_set("group", "member")));
+    }
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::persistance::schema::Network as DbNetwork;
+
+/// A tenant. Relation tuples and UUID mappings always belong to exactly one
+/// network and are removed with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Network {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbNetwork> for Network {
+    fn from(value: DbNetwork) -> Self {
+        Self {
+            id: value.id,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
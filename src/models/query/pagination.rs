@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::error::{HeimdallError, HeimdallResult};
+
+/// Largest page a client may ask for. Larger requests get this many items.
+pub const MAX_PAGE_SIZE: i32 = 1000;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenPagination {
     pub last_id: Option<Uuid>,
@@ -12,4 +17,63 @@ impl TokenPagination {
     pub fn encode_next_page_token(last_id: &Uuid) -> String {
         last_id.to_string()
     }
+
+    /// Reads a token produced by `encode_next_page_token`. The nil UUID marks
+    /// the last page and decodes to `None`.
+    pub fn decode_page_token(token: &str) -> HeimdallResult<Option<Uuid>> {
+        let last_id = Uuid::parse_str(token).map_err(|_| HeimdallError::MalformedInput)?;
+        Ok((!last_id.is_nil()).then_some(last_id))
+    }
+
+    /// Validates a requested page size, capping it at `MAX_PAGE_SIZE`.
+    pub fn checked_page_size(page_size: Option<i32>) -> HeimdallResult<Option<i32>> {
+        match page_size {
+            Some(page_size) if page_size <= 0 => Err(HeimdallError::MalformedInput),
+            page_size => Ok(page_size.map(|page_size| page_size.min(MAX_PAGE_SIZE))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_sizes_are_positive_and_capped() {
+        assert_eq!(TokenPagination::checked_page_size(None).unwrap(), None);
+        assert_eq!(
+            TokenPagination::checked_page_size(Some(1)).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            TokenPagination::checked_page_size(Some(MAX_PAGE_SIZE + 1)).unwrap(),
+            Some(MAX_PAGE_SIZE)
+        );
+        assert_eq!(
+            TokenPagination::checked_page_size(Some(i32::MAX)).unwrap(),
+            Some(MAX_PAGE_SIZE)
+        );
+        for page_size in [0, -1, i32::MIN] {
+            assert!(matches!(
+                TokenPagination::checked_page_size(Some(page_size)),
+                Err(HeimdallError::MalformedInput)
+            ));
+        }
+    }
+
+    #[test]
+    fn page_tokens_round_trip() {
+        let last_id = Uuid::new_v4();
+        let token = TokenPagination::encode_next_page_token(&last_id);
+        assert_eq!(
+            TokenPagination::decode_page_token(&token).unwrap(),
+            Some(last_id)
+        );
+        let last_page = TokenPagination::encode_next_page_token(&Uuid::nil());
+        assert_eq!(
+            TokenPagination::decode_page_token(&last_page).unwrap(),
+            None
+        );
+        assert!(TokenPagination::decode_page_token("not-a-token").is_err());
+    }
 }
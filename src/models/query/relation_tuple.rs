@@ -1,11 +1,42 @@
 use uuid::Uuid;
 
-use crate::models::relation_tuple::Subject;
+use crate::models::relation_tuple::{MapIds, RelationTuple, Subject};
 
 #[allow(unused)]
-pub struct RelationTupleQuery {
+pub struct RelationTupleQuery<Id = Uuid> {
     pub namespace: Option<String>,
-    pub object: Option<Uuid>,
+    pub object: Option<Id>,
     pub relation: Option<String>,
-    pub subject: Option<Subject>,
+    pub subject: Option<Subject<Id>>,
+}
+
+impl<Id> MapIds<Id> for RelationTupleQuery<Id> {
+    type Mapped<T> = RelationTupleQuery<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        if let Some(ref object) = self.object {
+            f(object);
+        }
+        self.subject.for_each_id(f);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        RelationTupleQuery {
+            namespace: self.namespace,
+            object: self.object.map(&mut *f),
+            relation: self.relation,
+            subject: self.subject.map_ids(f),
+        }
+    }
+}
+
+impl<Id: Clone> From<&RelationTuple<Id>> for RelationTupleQuery<Id> {
+    fn from(value: &RelationTuple<Id>) -> Self {
+        Self {
+            namespace: Some(value.namespace.clone()),
+            object: Some(value.object.clone()),
+            relation: Some(value.relation.clone()),
+            subject: Some(value.subject.clone()),
+        }
+    }
 }
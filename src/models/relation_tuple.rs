@@ -1,32 +1,153 @@
-use crate::persistance::schema::RelationTuple as DbRelationTuple;
-use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    error::{HeimdallError, HeimdallResult},
+    persistance::schema::RelationTuple as DbRelationTuple,
+};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Clone)]
-pub struct RelationTuple {
+/// Calls a function on every object and subject ID of a value, so the IDs can
+/// be mapped in one batch. The API takes IDs as strings and stores them as
+/// UUIDs, see `IdMapper`.
+pub trait MapIds<Id> {
+    type Mapped<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id));
+
+    /// Visits the IDs in the same order as `for_each_id`.
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T>;
+}
+
+impl<Id, M: MapIds<Id>> MapIds<Id> for Vec<M> {
+    type Mapped<T> = Vec<M::Mapped<T>>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        for value in self {
+            value.for_each_id(f);
+        }
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        self.into_iter().map(|value| value.map_ids(f)).collect()
+    }
+}
+
+impl<Id, M: MapIds<Id>> MapIds<Id> for Option<M> {
+    type Mapped<T> = Option<M::Mapped<T>>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        if let Some(value) = self {
+            value.for_each_id(f);
+        }
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        self.map(|value| value.map_ids(f))
+    }
+}
+
+/// A bare ID, e.g. an object found by a reverse lookup.
+impl MapIds<Uuid> for Uuid {
+    type Mapped<T> = T;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Uuid)) {
+        f(self);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Uuid) -> T) -> Self::Mapped<T> {
+        f(self)
+    }
+}
+
+/// Object and subject IDs are UUIDs everywhere but at the API, which uses
+/// strings.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct RelationTuple<Id = Uuid> {
     pub namespace: String,
-    pub object: Uuid,
+    pub object: Id,
     pub relation: String,
-    pub subject: Subject,
+    #[serde(flatten)]
+    pub subject: Subject<Id>,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub enum Subject {
-    Direct(SubjectID),
-    Set(SubjectSet),
+impl<Id> MapIds<Id> for RelationTuple<Id> {
+    type Mapped<T> = RelationTuple<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        f(&self.object);
+        self.subject.for_each_id(f);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        RelationTuple {
+            namespace: self.namespace,
+            object: f(self.object),
+            relation: self.relation,
+            subject: self.subject.map_ids(f),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct SubjectID {
-    pub id: Uuid,
+impl<Id: std::fmt::Display> std::fmt::Display for RelationTuple<Id> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}#{}@{}",
+            self.namespace, self.object, self.relation, self.subject
+        )
+    }
 }
 
-#[allow(unused)]
-impl SubjectID {
-    pub fn new(id: Uuid) -> Self {
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub enum Subject<Id = Uuid> {
+    #[serde(rename = "subject_id")]
+    Direct(SubjectID<Id>),
+    #[serde(rename = "subject_set")]
+    Set(SubjectSet<Id>),
+}
+
+impl<Id> MapIds<Id> for Subject<Id> {
+    type Mapped<T> = Subject<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        match self {
+            Subject::Direct(SubjectID { id }) => f(id),
+            Subject::Set(subject_set) => subject_set.for_each_id(f),
+        }
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        match self {
+            Subject::Direct(SubjectID { id }) => Subject::Direct(SubjectID::new(f(id))),
+            Subject::Set(subject_set) => Subject::Set(subject_set.map_ids(f)),
+        }
+    }
+}
+
+impl<Id: std::fmt::Display> std::fmt::Display for Subject<Id> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Subject::Direct(subject_id) => subject_id.fmt(f),
+            Subject::Set(subject_set) => subject_set.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct SubjectID<Id = Uuid> {
+    pub id: Id,
+}
+
+impl<Id> SubjectID<Id> {
+    pub fn new(id: Id) -> Self {
         Self { id }
     }
+}
 
+#[allow(unused)]
+impl SubjectID {
     pub fn unique_id(&self) -> Uuid {
         self.id
     }
@@ -39,29 +160,43 @@ impl SubjectID {
     }
 }
 
-impl std::fmt::Display for SubjectID {
+impl<Id: std::fmt::Display> std::fmt::Display for SubjectID<Id> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.id.fmt(f)
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct SubjectSet {
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct SubjectSet<Id = Uuid> {
     pub namespace: String,
-    pub object: Uuid,
+    pub object: Id,
     pub relation: String,
 }
 
-#[allow(unused)]
-impl SubjectSet {
-    pub fn new(namespace: String, object: Uuid, relation: String) -> Self {
+impl<Id> SubjectSet<Id> {
+    pub fn new(namespace: String, object: Id, relation: String) -> Self {
         Self {
             namespace,
             object,
             relation,
         }
     }
+}
+
+impl<Id> MapIds<Id> for SubjectSet<Id> {
+    type Mapped<T> = SubjectSet<T>;
 
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        f(&self.object);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        SubjectSet::new(self.namespace, f(self.object), self.relation)
+    }
+}
+
+#[allow(unused)]
+impl SubjectSet {
     pub fn unique_id(&self) -> Uuid {
         let namespace_relation = format!("{}-{}", self.namespace, self.relation);
         Uuid::new_v5(&self.object, namespace_relation.as_bytes())
@@ -83,9 +218,89 @@ impl SubjectSet {
     }
 }
 
-impl std::fmt::Display for SubjectSet {
+impl<Id: std::fmt::Display> std::fmt::Display for SubjectSet<Id> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}:{}#{}", self.namespace, self.object, self.relation)
+        write!(f, "{}:{}#{}", self.namespace, self.object, self.relation)
+    }
+}
+
+fn parse_error(input: &str, reason: &str) -> HeimdallError {
+    HeimdallError::Parse {
+        input: input.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Splits `namespace:object#relation`, where `#relation` may be left out.
+fn parse_object_relation(input: &str) -> HeimdallResult<(String, String, String)> {
+    let (namespace, rest) = input
+        .split_once(':')
+        .ok_or_else(|| parse_error(input, "expected namespace:object"))?;
+    let (object, relation) = rest.split_once('#').unwrap_or((rest, ""));
+
+    if namespace.is_empty() {
+        return Err(parse_error(input, "namespace is empty"));
+    }
+    if object.is_empty() {
+        return Err(parse_error(input, "object is empty"));
+    }
+
+    Ok((
+        namespace.to_string(),
+        object.to_string(),
+        relation.to_string(),
+    ))
+}
+
+/// Parses `namespace:object#relation`. The relation may be empty, e.g.
+/// `folder:reports` for the folder itself.
+impl FromStr for SubjectSet<String> {
+    type Err = HeimdallError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, object, relation) = parse_object_relation(s)?;
+        Ok(SubjectSet::new(namespace, object, relation))
+    }
+}
+
+/// Parses a subject set when the text contains a `#`, e.g. `group:eng#member`
+/// or `folder:reports#` for the folder itself, and a subject ID otherwise. IDs
+/// may contain `:` and `@`, like `user:alice@corp`, but not `#`.
+impl FromStr for Subject<String> {
+    type Err = HeimdallError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains('#') {
+            return Ok(Subject::Set(s.parse()?));
+        }
+        if s.is_empty() {
+            return Err(parse_error(s, "subject is empty"));
+        }
+        Ok(Subject::Direct(SubjectID::new(s.to_string())))
+    }
+}
+
+/// Parses the text form produced by `Display`, `namespace:object#relation@subject`.
+/// Objects may contain `@`, so the subject starts after the relation.
+impl FromStr for RelationTuple<String> {
+    type Err = HeimdallError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected = || parse_error(s, "expected namespace:object#relation@subject");
+        let (namespace_object, relation_subject) = s.split_once('#').ok_or_else(expected)?;
+        let (relation, subject) = relation_subject.split_once('@').ok_or_else(expected)?;
+        let (namespace, object, _) = parse_object_relation(namespace_object)?;
+
+        if relation.is_empty() {
+            return Err(parse_error(s, "relation is empty"));
+        }
+
+        Ok(RelationTuple {
+            namespace,
+            object,
+            relation: relation.to_string(),
+            subject: subject.parse()?,
+        })
     }
 }
 
@@ -95,16 +310,58 @@ impl From<DbRelationTuple> for RelationTuple {
             Subject::Direct(SubjectID { id })
         } else {
             Subject::Set(SubjectSet {
-                namespace: value.namespace.clone(),
-                object: value.object,
-                relation: value.relation.clone(),
+                namespace: value.subject_set_namespace.unwrap_or_default(),
+                object: value.subject_set_object.unwrap_or_default(),
+                relation: value.subject_set_relation.unwrap_or_default(),
             })
         };
         Self {
-            namespace: value.namespace.clone(),
+            namespace: value.namespace,
             object: value.object,
-            relation: value.relation.clone(),
+            relation: value.relation,
             subject,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subject_ids_may_contain_colons_and_at_signs() {
+        let tuple: RelationTuple<String> = "doc:x#viewer@user:alice@corp".parse().unwrap();
+        assert!(matches!(&tuple.subject, Subject::Direct(id) if id.id == "user:alice@corp"));
+        assert_eq!(
+            tuple.to_string().parse::<RelationTuple<String>>().unwrap(),
+            tuple
+        );
+    }
+
+    #[test]
+    fn object_ids_may_contain_at_signs() {
+        let tuple: RelationTuple<String> = "doc:report@2024#viewer@user:alice".parse().unwrap();
+        assert_eq!(tuple.object, "report@2024");
+        assert_eq!(tuple.relation, "viewer");
+        assert!(matches!(&tuple.subject, Subject::Direct(id) if id.id == "user:alice"));
+        assert_eq!(
+            tuple.to_string().parse::<RelationTuple<String>>().unwrap(),
+            tuple
+        );
+    }
+
+    #[test]
+    fn subject_sets_need_a_hash() {
+        let tuple: RelationTuple<String> = "doc:x#parent@folder:docs#".parse().unwrap();
+        assert!(matches!(
+            &tuple.subject,
+            Subject::Set(set) if set.namespace == "folder" && set.object == "docs" && set.relation.is_empty()
+        ));
+        assert_eq!(
+            tuple.to_string().parse::<RelationTuple<String>>().unwrap(),
+            tuple
+        );
+
+        assert!("alice#1".parse::<Subject<String>>().is_err());
+    }
+}
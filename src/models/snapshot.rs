@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{HeimdallError, HeimdallResult};
+
+/// Version prefix, so the encoding can change without breaking old tokens.
+const SNAPSHOT_TOKEN_PREFIX: &str = "v1.";
+
+/// Opaque token naming the snapshot a write was committed at. Clients hand it
+/// back on reads that must not be evaluated on older data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SnapshotToken {
+    commit_time: DateTime<Utc>,
+}
+
+#[allow(unused)]
+impl SnapshotToken {
+    pub fn commit_time(&self) -> &DateTime<Utc> {
+        &self.commit_time
+    }
+}
+
+impl From<DateTime<Utc>> for SnapshotToken {
+    fn from(commit_time: DateTime<Utc>) -> Self {
+        Self { commit_time }
+    }
+}
+
+impl std::fmt::Display for SnapshotToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{SNAPSHOT_TOKEN_PREFIX}{:x}",
+            self.commit_time.timestamp_micros()
+        )
+    }
+}
+
+impl FromStr for SnapshotToken {
+    type Err = HeimdallError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix(SNAPSHOT_TOKEN_PREFIX)
+            .and_then(|micros| i64::from_str_radix(micros, 16).ok())
+            .and_then(DateTime::from_timestamp_micros)
+            .map(|commit_time| Self { commit_time })
+            .ok_or_else(|| HeimdallError::Parse {
+                input: s.to_string(),
+                reason: "not a snapshot token".to_string(),
+            })
+    }
+}
+
+impl Serialize for SnapshotToken {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SnapshotToken {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Which snapshot a read is evaluated at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Consistency {
+    /// Every committed tuple.
+    #[default]
+    Latest,
+    /// Every tuple committed up to the token's snapshot, and maybe later ones.
+    /// Reads always go to the primary, so this is answered like `Latest`.
+    AtLeastAsFresh(SnapshotToken),
+    /// Only tuples committed at or before the token's snapshot. Deletes are
+    /// not versioned, so snapshots older than the network's last delete are
+    /// rejected rather than read without the deleted tuples.
+    AtExactSnapshot(SnapshotToken),
+}
+
+#[allow(unused)]
+impl Consistency {
+    /// The latest commit time a read may see, if limited.
+    pub fn snapshot(&self) -> Option<&DateTime<Utc>> {
+        match self {
+            Consistency::Latest | Consistency::AtLeastAsFresh(_) => None,
+            Consistency::AtExactSnapshot(token) => Some(token.commit_time()),
+        }
+    }
+
+    /// Fails when the snapshot is older than `last_delete`, the commit time of
+    /// the network's most recent delete.
+    pub fn check_readable(&self, last_delete: Option<&DateTime<Utc>>) -> HeimdallResult<()> {
+        match (self, last_delete) {
+            (Consistency::AtExactSnapshot(token), Some(last_delete))
+                if token.commit_time() < last_delete =>
+            {
+                Err(HeimdallError::SnapshotExpired(*token))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token() -> SnapshotToken {
+        SnapshotToken::from(DateTime::from_timestamp_micros(1_791_000_000_123_456).unwrap())
+    }
+
+    #[test]
+    fn tokens_round_trip_through_text() {
+        let text = token().to_string();
+        assert!(text.starts_with(SNAPSHOT_TOKEN_PREFIX));
+        assert_eq!(text.parse::<SnapshotToken>().unwrap(), token());
+
+        let json = serde_json::to_string(&token()).unwrap();
+        assert_eq!(json, format!("\"{text}\""));
+        assert_eq!(
+            serde_json::from_str::<SnapshotToken>(&json).unwrap(),
+            token()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        for text in ["", "v1.", "v2.65e0280b4bd94", "65e0280b4bd94", "v1.xyz"] {
+            assert!(
+                matches!(
+                    text.parse::<SnapshotToken>(),
+                    Err(HeimdallError::Parse { .. })
+                ),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn only_exact_snapshots_limit_reads() {
+        assert_eq!(Consistency::Latest.snapshot(), None);
+        assert_eq!(Consistency::AtLeastAsFresh(token()).snapshot(), None);
+        assert_eq!(
+            Consistency::AtExactSnapshot(token()).snapshot(),
+            Some(token().commit_time())
+        );
+    }
+
+    #[test]
+    fn rejects_exact_snapshots_older_than_the_last_delete() {
+        let at = |micros: i64| DateTime::from_timestamp_micros(micros).unwrap();
+        let exact = Consistency::AtExactSnapshot(token());
+        let commit_time = token().commit_time().timestamp_micros();
+
+        assert!(exact.check_readable(None).is_ok());
+        assert!(exact.check_readable(Some(&at(commit_time))).is_ok());
+        assert!(matches!(
+            exact.check_readable(Some(&at(commit_time + 1))),
+            Err(HeimdallError::SnapshotExpired(expired)) if expired == token()
+        ));
+
+        let later = at(commit_time + 1);
+        assert!(Consistency::Latest.check_readable(Some(&later)).is_ok());
+        assert!(
+            Consistency::AtLeastAsFresh(token())
+                .check_readable(Some(&later))
+                .is_ok()
+        );
+    }
+}
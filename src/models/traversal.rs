@@ -1,14 +1,50 @@
 #![allow(unused)]
 
-use super::relation_tuple::RelationTuple;
+use serde::Serialize;
+use uuid::Uuid;
 
-pub struct TraversalResult {
-    pub from: RelationTuple,
-    pub to: RelationTuple,
+use super::relation_tuple::{MapIds, RelationTuple};
+
+/// One step of a check: `from` holds if `to` does. `found` tells whether `to`
+/// is stored as-is.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraversalResult<Id = Uuid> {
+    pub from: RelationTuple<Id>,
+    pub to: RelationTuple<Id>,
     pub via: Traversal,
     pub found: bool,
 }
 
+impl<Id> MapIds<Id> for TraversalResult<Id> {
+    type Mapped<T> = TraversalResult<T>;
+
+    fn for_each_id(&self, f: &mut impl FnMut(&Id)) {
+        self.from.for_each_id(f);
+        self.to.for_each_id(f);
+    }
+
+    fn map_ids<T>(self, f: &mut impl FnMut(Id) -> T) -> Self::Mapped<T> {
+        TraversalResult {
+            from: self.from.map_ids(f),
+            to: self.to.map_ids(f),
+            via: self.via,
+            found: self.found,
+        }
+    }
+}
+
+impl<Id: std::fmt::Display> std::fmt::Display for TraversalResult<Id> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} ({})", self.from, self.to, self.via)?;
+        if self.found {
+            write!(f, ", stored")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Traversal {
     Unknown,
     SubjectSetExpand,
@@ -19,10 +55,10 @@ pub enum Traversal {
 impl std::fmt::Display for Traversal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Traversal::Unknown => writeln!(f, "unknown"),
-            Traversal::SubjectSetExpand => writeln!(f, "subject set expand"),
-            Traversal::ComputedUserset => writeln!(f, "computed userset"),
-            Traversal::TupleToUserset => writeln!(f, "tuple to userset"),
+            Traversal::Unknown => write!(f, "unknown"),
+            Traversal::SubjectSetExpand => write!(f, "subject set expand"),
+            Traversal::ComputedUserset => write!(f, "computed userset"),
+            Traversal::TupleToUserset => write!(f, "tuple to userset"),
         }
     }
 }
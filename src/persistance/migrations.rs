@@ -1 +1,150 @@
+use std::collections::HashMap;
 
+use sqlx::{
+    PgPool,
+    migrate::{Migrate, Migrator},
+};
+
+use crate::error::{HeimdallError, HeimdallResult};
+
+/// Migrations under `migrations/postgres`, embedded at compile time so the
+/// binary can migrate the database it serves without the SQL files around.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the embedded SQL changed since.
+    Modified,
+    /// Failed part way through and has to be fixed by hand.
+    Dirty,
+    /// Applied by a newer binary.
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+pub struct MigrationRunner {
+    pool: PgPool,
+}
+
+impl MigrationRunner {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Applies every pending migration.
+    pub async fn up(&self) -> HeimdallResult<()> {
+        MIGRATOR.run(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Reverts the last `steps` applied migrations and returns their versions.
+    pub async fn down(&self, steps: usize) -> HeimdallResult<Vec<i64>> {
+        let mut conn = self.pool.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        let applied: Vec<i64> = conn
+            .list_applied_migrations()
+            .await?
+            .into_iter()
+            .map(|migration| migration.version)
+            .collect();
+        drop(conn);
+
+        // Everything newer than `target` is reverted, 0 reverts all of them.
+        let kept = applied.len().saturating_sub(steps);
+        let target = kept.checked_sub(1).map_or(0, |index| applied[index]);
+
+        MIGRATOR.undo(&self.pool, target).await?;
+        Ok(applied[kept..].iter().rev().copied().collect())
+    }
+
+    pub async fn status(&self) -> HeimdallResult<Vec<MigrationStatus>> {
+        let mut conn = self.pool.acquire().await?;
+
+        // Only reads, so a database that was never migrated is left untouched.
+        let migrated: bool =
+            sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                .fetch_one(&mut *conn)
+                .await?;
+        let (dirty, mut applied) = if migrated {
+            let applied: HashMap<i64, Vec<u8>> = conn
+                .list_applied_migrations()
+                .await?
+                .into_iter()
+                .map(|migration| (migration.version, migration.checksum.into_owned()))
+                .collect();
+            (conn.dirty_version().await?, applied)
+        } else {
+            (None, HashMap::new())
+        };
+
+        let mut statuses: Vec<MigrationStatus> = MIGRATOR
+            .iter()
+            .filter(|migration| migration.migration_type.is_up_migration())
+            .map(|migration| {
+                let state = match applied.remove(&migration.version) {
+                    _ if dirty == Some(migration.version) => MigrationState::Dirty,
+                    Some(checksum) if checksum == *migration.checksum => MigrationState::Applied,
+                    Some(_) => MigrationState::Modified,
+                    None => MigrationState::Pending,
+                };
+                MigrationStatus {
+                    version: migration.version,
+                    description: migration.description.to_string(),
+                    state,
+                }
+            })
+            .collect();
+
+        statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+            version,
+            description: String::new(),
+            state: MigrationState::Unknown,
+        }));
+        statuses.sort_by_key(|status| status.version);
+
+        Ok(statuses)
+    }
+
+    /// Fails when the database is missing migrations this binary expects, so
+    /// a server never runs queries against an older schema.
+    pub async fn ensure_up_to_date(&self) -> HeimdallResult<()> {
+        let behind: Vec<i64> = self
+            .status()
+            .await?
+            .into_iter()
+            .filter(|status| {
+                matches!(
+                    status.state,
+                    MigrationState::Pending | MigrationState::Dirty
+                )
+            })
+            .map(|status| status.version)
+            .collect();
+
+        if behind.is_empty() {
+            Ok(())
+        } else {
+            Err(HeimdallError::PendingMigrations(behind))
+        }
+    }
+}
+
+impl std::fmt::Display for MigrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationState::Applied => write!(f, "applied"),
+            MigrationState::Pending => write!(f, "pending"),
+            MigrationState::Modified => write!(f, "modified"),
+            MigrationState::Dirty => write!(f, "dirty"),
+            MigrationState::Unknown => write!(f, "unknown"),
+        }
+    }
+}
@@ -1,2 +1,2 @@
-mod migrations;
+pub mod migrations;
 pub mod schema;
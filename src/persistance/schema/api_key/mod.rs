@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub nid: Uuid,
+    pub name: String,
+    pub key_hash: String,
+    pub scope: String,
+    pub created_at: DateTime<Utc>,
+}
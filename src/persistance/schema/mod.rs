@@ -1,7 +1,13 @@
+mod api_key;
+mod network;
 mod relation_tuple;
+mod relation_tuple_change;
 mod traversal;
 mod uuid_mapping;
 
+pub use self::api_key::ApiKey;
+pub use self::network::Network;
 pub use self::relation_tuple::RelationTuple;
-pub use self::traversal::SubjectExapandedRelationTupleRow;
+pub use self::relation_tuple_change::RelationTupleChange;
+pub use self::traversal::{SubjectExapandedRelationTupleRow, SubjectSetRewriteRow};
 pub use self::uuid_mapping::UuidMapping;
@@ -8,3 +8,9 @@ pub struct SubjectExapandedRelationTupleRow {
     pub subject_set_relation: String,
     pub found: bool,
 }
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct SubjectSetRewriteRow {
+    pub relation: String,
+    pub found: bool,
+}
@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::info_span;
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::api_key::{ApiKey, ApiKeyScope, CreatedApiKey, generate_api_key, hash_api_key},
+    persistance::schema::ApiKey as DbApiKey,
+};
+
+use super::traits::ApiKeyManager;
+
+pub struct ApiKeyService {
+    pool: PgPool,
+}
+
+impl ApiKeyService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ApiKeyManager for ApiKeyService {
+    async fn create_api_key(
+        &self,
+        ctx: &RequestContext,
+        scope: ApiKeyScope,
+        name: &str,
+    ) -> HeimdallResult<CreatedApiKey> {
+        let span = info_span!("create_api_key");
+        let _guard = span.enter();
+
+        let secret = generate_api_key();
+
+        let api_key: DbApiKey = sqlx::query_as(
+            "INSERT INTO heimdall_api_keys (id, nid, name, key_hash, scope, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, nid, name, key_hash, scope, created_at",
+        )
+        .bind(Uuid::new_v4())
+        .bind(ctx.network_id())
+        .bind(name)
+        .bind(hash_api_key(&secret))
+        .bind(scope.to_string())
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                HeimdallError::NetworkNotFound(*ctx.network_id())
+            }
+            e => e.into(),
+        })?;
+
+        Ok(CreatedApiKey {
+            api_key: api_key.try_into()?,
+            secret,
+        })
+    }
+
+    async fn list_api_keys(&self, ctx: &RequestContext) -> HeimdallResult<Vec<ApiKey>> {
+        let span = info_span!("list_api_keys");
+        let _guard = span.enter();
+
+        let api_keys: Vec<DbApiKey> = sqlx::query_as(
+            "SELECT id, nid, name, key_hash, scope, created_at FROM heimdall_api_keys
+            WHERE nid = $1 ORDER BY created_at, id",
+        )
+        .bind(ctx.network_id())
+        .fetch_all(&self.pool)
+        .await?;
+
+        api_keys.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn delete_api_key(&self, ctx: &RequestContext, id: Uuid) -> HeimdallResult<()> {
+        let span = info_span!("delete_api_key");
+        let _guard = span.enter();
+
+        let result = sqlx::query("DELETE FROM heimdall_api_keys WHERE nid = $1 AND id = $2")
+            .bind(ctx.network_id())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(HeimdallError::ApiKeyNotFound(id));
+        }
+        Ok(())
+    }
+
+    async fn authenticate(&self, secret: &str) -> HeimdallResult<Option<ApiKey>> {
+        let span = info_span!("authenticate");
+        let _guard = span.enter();
+
+        let api_key: Option<DbApiKey> = sqlx::query_as(
+            "SELECT id, nid, name, key_hash, scope, created_at FROM heimdall_api_keys
+            WHERE key_hash = $1",
+        )
+        .bind(hash_api_key(secret))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        api_key.map(TryInto::try_into).transpose()
+    }
+}
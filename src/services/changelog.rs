@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::info_span;
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::{change::RelationTupleChange, snapshot::SnapshotToken},
+    persistance::schema::RelationTupleChange as DbRelationTupleChange,
+};
+
+use super::traits::ChangelogManager;
+
+pub struct ChangelogService {
+    pool: PgPool,
+}
+
+impl ChangelogService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ChangelogManager for ChangelogService {
+    async fn change_cursor(
+        &self,
+        ctx: &RequestContext,
+        snapshot: Option<&SnapshotToken>,
+    ) -> HeimdallResult<i64> {
+        let span = info_span!("change_cursor");
+        let _guard = span.enter();
+
+        let cursor: Option<i64> = sqlx::query_scalar(
+            "SELECT MAX(id) FROM heimdall_relation_tuple_changes
+            WHERE nid = $1 AND ($2::TIMESTAMPTZ IS NULL OR commit_time <= $2)",
+        )
+        .bind(ctx.network_id())
+        .bind(snapshot.map(SnapshotToken::commit_time))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(cursor.unwrap_or_default())
+    }
+
+    async fn list_changes(
+        &self,
+        ctx: &RequestContext,
+        cursor: i64,
+        limit: i64,
+    ) -> HeimdallResult<Vec<RelationTupleChange>> {
+        let span = info_span!("list_changes");
+        let _guard = span.enter();
+
+        let changes: Vec<DbRelationTupleChange> = sqlx::query_as(
+            "SELECT id, nid, action, namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation, commit_time
+            FROM heimdall_relation_tuple_changes
+            WHERE nid = $1 AND id > $2 ORDER BY id LIMIT $3",
+        )
+        .bind(ctx.network_id())
+        .bind(cursor)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        changes.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn last_delete(&self, ctx: &RequestContext) -> HeimdallResult<Option<DateTime<Utc>>> {
+        let span = info_span!("last_delete");
+        let _guard = span.enter();
+
+        let last_delete = sqlx::query_scalar(
+            "SELECT MAX(commit_time) FROM heimdall_relation_tuple_changes
+            WHERE nid = $1 AND action = 'delete'",
+        )
+        .bind(ctx.network_id())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(last_delete)
+    }
+}
@@ -0,0 +1,324 @@
+//! In-memory stand-ins for the Postgres services, so the engines can be tested
+//! without a database.
+
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use config::{Config, File, FileFormat};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::{
+        namespace::Namespace,
+        query::{TokenPagination, relation_tuple::RelationTupleQuery},
+        relation_tuple::{MapIds, RelationTuple, Subject, SubjectSet},
+        response::PaginatedResponse,
+        snapshot::SnapshotToken,
+        traversal::{Traversal, TraversalResult},
+    },
+    persistance::schema::UuidMapping,
+};
+
+use super::{
+    Services,
+    api_key::ApiKeyService,
+    changelog::ChangelogService,
+    namespace::NamespaceService,
+    network::NetworkService,
+    traits::{RelationTupleManager, TraversalManager},
+    uuid_mapper::UuidMappingService,
+};
+
+/// The UUID a test ID stands for.
+pub fn uuid(id: &str) -> Uuid {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, id.as_bytes())
+}
+
+/// Parses `namespace:object#relation@subject`, mapping the IDs with `uuid`.
+pub fn tuple(s: &str) -> RelationTuple {
+    RelationTuple::<String>::from_str(s)
+        .unwrap()
+        .map_ids(&mut |id| uuid(&id))
+}
+
+pub fn subject_set(s: &str) -> SubjectSet {
+    SubjectSet::<String>::from_str(s)
+        .unwrap()
+        .map_ids(&mut |id| uuid(&id))
+}
+
+pub fn context() -> RequestContext {
+    RequestContext::new(Uuid::nil(), String::new(), String::new())
+}
+
+/// Reads a `namespaces` list written in YAML.
+pub fn namespaces(yaml: &str) -> Vec<Namespace> {
+    Config::builder()
+        .add_source(File::from_str(yaml, FileFormat::Yaml))
+        .build()
+        .unwrap()
+        .get("namespaces")
+        .unwrap()
+}
+
+/// Services reading `tuples` from memory. The services the engines do not use
+/// get a pool that never connects.
+pub fn services(yaml: &str, tuples: &[&str]) -> Services {
+    let store = Arc::new(MemoryStore::new(tuples.iter().map(|s| tuple(s)).collect()));
+    let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+    Services {
+        relation_tuple_service: store.clone(),
+        uuid_mapping_service: Arc::new(UuidMappingService::new(pool.clone())),
+        traversal_service: store,
+        namespace_service: Arc::new(NamespaceService::new(namespaces(yaml)).unwrap()),
+        network_service: Arc::new(NetworkService::new(pool.clone())),
+        api_key_service: Arc::new(ApiKeyService::new(pool.clone())),
+        changelog_service: Arc::new(ChangelogService::new(pool)),
+    }
+}
+
+/// Tuples kept in insertion order, which stands in for shard order. Like the
+/// Postgres services, traversals return every step they find.
+pub struct MemoryStore {
+    tuples: Mutex<Vec<RelationTuple>>,
+}
+
+impl MemoryStore {
+    pub fn new(tuples: Vec<RelationTuple>) -> Self {
+        Self {
+            tuples: Mutex::new(tuples),
+        }
+    }
+
+    fn contains(&self, tuple: &RelationTuple) -> bool {
+        self.tuples.lock().unwrap().contains(tuple)
+    }
+
+    fn matching(&self, query: &RelationTupleQuery) -> Vec<RelationTuple> {
+        self.tuples
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| {
+                query.namespace.as_ref().is_none_or(|n| *n == r.namespace)
+                    && query.object.is_none_or(|o| o == r.object)
+                    && query.relation.as_ref().is_none_or(|rel| *rel == r.relation)
+                    && query.subject.as_ref().is_none_or(|s| *s == r.subject)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The subject sets stored on `namespace:object#relation`, in order.
+    fn subject_sets(&self, namespace: &str, object: Uuid, relation: &str) -> Vec<SubjectSet> {
+        self.tuples
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.namespace == namespace && r.object == object && r.relation == relation)
+            .filter_map(|r| match r.subject {
+                Subject::Set(ref subject_set) => Some(subject_set.clone()),
+                Subject::Direct(_) => None,
+            })
+            .collect()
+    }
+
+    fn step(&self, from: &RelationTuple, to: RelationTuple, via: Traversal) -> TraversalResult {
+        let found = self.contains(&to);
+        TraversalResult {
+            from: from.clone(),
+            to,
+            via,
+            found,
+        }
+    }
+}
+
+#[async_trait]
+impl RelationTupleManager for MemoryStore {
+    async fn write_relation_tuples(
+        &self,
+        _ctx: &RequestContext,
+        rs: &[RelationTuple],
+        _mappings: &[UuidMapping],
+    ) -> HeimdallResult<SnapshotToken> {
+        let mut tuples = self.tuples.lock().unwrap();
+        for r in rs {
+            if !tuples.contains(r) {
+                tuples.push(r.clone());
+            }
+        }
+        Ok(SnapshotToken::from(Utc::now()))
+    }
+
+    async fn get_relation_tuples(
+        &self,
+        _ctx: &RequestContext,
+        rs_query: &RelationTupleQuery,
+        _pagination_params: &TokenPagination,
+    ) -> HeimdallResult<PaginatedResponse<Vec<RelationTuple>>> {
+        Ok(PaginatedResponse {
+            data: self.matching(rs_query),
+            token: TokenPagination::encode_next_page_token(&Uuid::nil()),
+        })
+    }
+
+    async fn exists_relation_tuples(
+        &self,
+        _ctx: &RequestContext,
+        rs_query: &RelationTupleQuery,
+    ) -> HeimdallResult<bool> {
+        Ok(!self.matching(rs_query).is_empty())
+    }
+
+    async fn exists_relation_tuples_batch(
+        &self,
+        _ctx: &RequestContext,
+        rs: &[RelationTuple],
+    ) -> HeimdallResult<Vec<bool>> {
+        Ok(rs.iter().map(|r| self.contains(r)).collect())
+    }
+
+    async fn delete_relation_tuples(
+        &self,
+        _ctx: &RequestContext,
+        rs: &[RelationTuple],
+    ) -> HeimdallResult<SnapshotToken> {
+        self.tuples.lock().unwrap().retain(|r| !rs.contains(r));
+        Ok(SnapshotToken::from(Utc::now()))
+    }
+
+    async fn transact_relation_tuples(
+        &self,
+        ctx: &RequestContext,
+        inserts: &[RelationTuple],
+        deletes: &[RelationTuple],
+        mappings: &[UuidMapping],
+    ) -> HeimdallResult<SnapshotToken> {
+        self.delete_relation_tuples(ctx, deletes).await?;
+        self.write_relation_tuples(ctx, inserts, mappings).await
+    }
+
+    async fn delete_all_relation_tuples(
+        &self,
+        _ctx: &RequestContext,
+        rs_query: &RelationTupleQuery,
+    ) -> HeimdallResult<SnapshotToken> {
+        let matching = self.matching(rs_query);
+        self.tuples
+            .lock()
+            .unwrap()
+            .retain(|r| !matching.contains(r));
+        Ok(SnapshotToken::from(Utc::now()))
+    }
+}
+
+#[async_trait]
+impl TraversalManager for MemoryStore {
+    async fn traverse_subject_set_expansion(
+        &self,
+        _ctx: &RequestContext,
+        start: &RelationTuple,
+    ) -> HeimdallResult<Vec<TraversalResult>> {
+        let subject_sets = self.subject_sets(&start.namespace, start.object, &start.relation);
+        Ok(subject_sets
+            .into_iter()
+            .map(|subject_set| {
+                let to = RelationTuple {
+                    namespace: subject_set.namespace,
+                    object: subject_set.object,
+                    relation: subject_set.relation,
+                    subject: start.subject.clone(),
+                };
+                self.step(start, to, Traversal::SubjectSetExpand)
+            })
+            .collect())
+    }
+
+    async fn traverse_subject_set_rewrite(
+        &self,
+        _ctx: &RequestContext,
+        start: &RelationTuple,
+        computed_subject_sets: &[String],
+    ) -> HeimdallResult<Vec<TraversalResult>> {
+        Ok(computed_subject_sets
+            .iter()
+            .map(|relation| {
+                let to = RelationTuple {
+                    relation: relation.clone(),
+                    ..start.clone()
+                };
+                self.step(start, to, Traversal::ComputedUserset)
+            })
+            .collect())
+    }
+
+    async fn traverse_tuple_to_userset(
+        &self,
+        _ctx: &RequestContext,
+        start: &RelationTuple,
+        tupleset_relation: &str,
+        computed_relation: &str,
+    ) -> HeimdallResult<Vec<TraversalResult>> {
+        let subject_sets = self.subject_sets(&start.namespace, start.object, tupleset_relation);
+        Ok(subject_sets
+            .into_iter()
+            .map(|subject_set| {
+                let to = RelationTuple {
+                    namespace: subject_set.namespace,
+                    object: subject_set.object,
+                    relation: computed_relation.to_string(),
+                    subject: start.subject.clone(),
+                };
+                self.step(start, to, Traversal::TupleToUserset)
+            })
+            .collect())
+    }
+
+    async fn traverse_subject_set_members(
+        &self,
+        _ctx: &RequestContext,
+        subject_set: &SubjectSet,
+    ) -> HeimdallResult<Vec<Subject>> {
+        Ok(self
+            .tuples
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| {
+                r.namespace == subject_set.namespace
+                    && r.object == subject_set.object
+                    && r.relation == subject_set.relation
+            })
+            .map(|r| r.subject.clone())
+            .collect())
+    }
+
+    async fn traverse_reverse(
+        &self,
+        _ctx: &RequestContext,
+        subject: &Subject,
+    ) -> HeimdallResult<Vec<RelationTuple>> {
+        Ok(self
+            .tuples
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| match (subject, &r.subject) {
+                (Subject::Direct(id), Subject::Direct(stored)) => id == stored,
+                (Subject::Set(set), Subject::Set(stored)) => {
+                    set.namespace == stored.namespace && set.object == stored.object
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect())
+    }
+}
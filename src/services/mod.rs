@@ -1,11 +1,23 @@
 use std::sync::Arc;
 
+use api_key::ApiKeyService;
+use changelog::ChangelogService;
+use namespace::NamespaceService;
+use network::NetworkService;
 use relation_tuple::RelationTupleService;
 use sqlx::PgPool;
-use traits::{RelationTupleManager, TraversalManager, UuidMappingManager};
+use traits::{
+    ApiKeyManager, ChangelogManager, NamespaceManager, NetworkManager, RelationTupleManager,
+    TraversalManager, UuidMappingManager,
+};
 use traversal::TraversalService;
 use uuid_mapper::UuidMappingService;
 
+pub mod api_key;
+pub mod changelog;
+#[cfg(test)]
+pub mod memory;
+pub mod namespace;
 pub mod network;
 pub mod relation_tuple;
 pub mod traits;
@@ -18,18 +30,33 @@ pub struct Services {
     pub relation_tuple_service: Arc<dyn RelationTupleManager>,
     pub uuid_mapping_service: Arc<dyn UuidMappingManager>,
     pub traversal_service: Arc<dyn TraversalManager>,
+    pub namespace_service: Arc<dyn NamespaceManager>,
+    pub network_service: Arc<dyn NetworkManager>,
+    pub api_key_service: Arc<dyn ApiKeyManager>,
+    pub changelog_service: Arc<dyn ChangelogManager>,
 }
 
 #[allow(unused)]
 impl Services {
-    pub fn new(pool: PgPool) -> Self {
-        let relation_tuple_service = Arc::new(RelationTupleService::new(pool.clone()));
+    pub fn new(pool: PgPool, namespace_service: NamespaceService) -> Self {
+        let namespace_service: Arc<dyn NamespaceManager> = Arc::new(namespace_service);
+        let relation_tuple_service = Arc::new(RelationTupleService::new(
+            pool.clone(),
+            namespace_service.clone(),
+        ));
         let uuid_mapping_service = Arc::new(UuidMappingService::new(pool.clone()));
         let traversal_service = Arc::new(TraversalService::new(pool.clone()));
+        let network_service = Arc::new(NetworkService::new(pool.clone()));
+        let api_key_service = Arc::new(ApiKeyService::new(pool.clone()));
+        let changelog_service = Arc::new(ChangelogService::new(pool.clone()));
         Self {
             relation_tuple_service,
             uuid_mapping_service,
             traversal_service,
+            namespace_service,
+            network_service,
+            api_key_service,
+            changelog_service,
         }
     }
 }
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use config::{Config, ConfigError, File};
+
+use crate::{
+    context::RequestContext,
+    error::{HeimdallError, HeimdallResult},
+    models::namespace::{Namespace, Relation, Rewrite},
+};
+
+use super::traits::NamespaceManager;
+
+/// Serves the namespaces declared in configuration. Namespaces are loaded once
+/// at startup and validated before the service is handed out.
+#[derive(Debug)]
+pub struct NamespaceService {
+    namespaces: HashMap<String, Namespace>,
+}
+
+#[allow(unused)]
+impl NamespaceService {
+    pub fn new(namespaces: Vec<Namespace>) -> HeimdallResult<Self> {
+        let mut by_name = HashMap::with_capacity(namespaces.len());
+
+        for namespace in namespaces {
+            Self::validate(&namespace)?;
+            if by_name.contains_key(&namespace.name) {
+                return Err(HeimdallError::InvalidNamespaceConfig(format!(
+                    "namespace {} is declared more than once",
+                    namespace.name
+                )));
+            }
+            by_name.insert(namespace.name.clone(), namespace);
+        }
+
+        Ok(Self {
+            namespaces: by_name,
+        })
+    }
+
+    /// Reads the `namespaces` list from a YAML, TOML or JSON5 file. A file
+    /// without the key declares no namespaces.
+    pub fn from_config_file(path: &str) -> HeimdallResult<Self> {
+        let config = Config::builder()
+            .add_source(File::with_name(path))
+            .build()?;
+
+        let namespaces = match config.get::<Vec<Namespace>>("namespaces") {
+            Ok(namespaces) => namespaces,
+            Err(ConfigError::NotFound(_)) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Self::new(namespaces)
+    }
+
+    fn validate(namespace: &Namespace) -> HeimdallResult<()> {
+        for (idx, relation) in namespace.relations.iter().enumerate() {
+            if namespace.relations[..idx]
+                .iter()
+                .any(|other| other.name == relation.name)
+            {
+                return Err(HeimdallError::InvalidNamespaceConfig(format!(
+                    "relation {}#{} is declared more than once",
+                    namespace.name, relation.name
+                )));
+            }
+            if let Some(ref rewrite) = relation.rewrite {
+                Self::validate_rewrite(namespace, relation, rewrite)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Computed usersets and tuplesets always point at relations of the same
+    /// namespace, so they can be checked here. The computed relation of a
+    /// tuple-to-userset lives on whatever namespace the tupleset points at and
+    /// is resolved at check time.
+    fn validate_rewrite(
+        namespace: &Namespace,
+        relation: &Relation,
+        rewrite: &Rewrite,
+    ) -> HeimdallResult<()> {
+        let unknown_relation = |name: &str| {
+            HeimdallError::InvalidNamespaceConfig(format!(
+                "relation {}#{} references unknown relation {}",
+                namespace.name, relation.name, name
+            ))
+        };
+
+        match rewrite {
+            Rewrite::This => Ok(()),
+            Rewrite::ComputedUserset { relation: computed } => namespace
+                .relation(computed)
+                .map(|_| ())
+                .ok_or_else(|| unknown_relation(computed)),
+            Rewrite::TupleToUserset { tupleset, .. } => namespace
+                .relation(tupleset)
+                .map(|_| ())
+                .ok_or_else(|| unknown_relation(tupleset)),
+            Rewrite::Union { children } | Rewrite::Intersection { children } => children
+                .iter()
+                .try_for_each(|child| Self::validate_rewrite(namespace, relation, child)),
+            Rewrite::Exclusion { base, subtract } => {
+                Self::validate_rewrite(namespace, relation, base)?;
+                Self::validate_rewrite(namespace, relation, subtract)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl NamespaceManager for NamespaceService {
+    async fn get_namespace(&self, _ctx: &RequestContext, name: &str) -> HeimdallResult<Namespace> {
+        self.namespaces
+            .get(name)
+            .cloned()
+            .ok_or_else(|| HeimdallError::NamespaceNotFound(name.to_string()))
+    }
+
+    async fn get_relation(
+        &self,
+        _ctx: &RequestContext,
+        namespace: &str,
+        relation: &str,
+    ) -> HeimdallResult<Relation> {
+        self.namespaces
+            .get(namespace)
+            .ok_or_else(|| HeimdallError::NamespaceNotFound(namespace.to_string()))?
+            .relation(relation)
+            .cloned()
+            .ok_or_else(|| HeimdallError::RelationNotFound {
+                namespace: namespace.to_string(),
+                relation: relation.to_string(),
+            })
+    }
+
+    async fn list_namespaces(&self, _ctx: &RequestContext) -> HeimdallResult<Vec<Namespace>> {
+        let mut namespaces: Vec<Namespace> = self.namespaces.values().cloned().collect();
+        namespaces.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(namespaces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::memory::{context, namespaces};
+
+    use super::*;
+
+    fn load(yaml: &str) -> HeimdallResult<NamespaceService> {
+        NamespaceService::new(namespaces(yaml))
+    }
+
+    fn assert_invalid(yaml: &str) {
+        assert!(matches!(
+            load(yaml),
+            Err(HeimdallError::InvalidNamespaceConfig(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn serves_declared_namespaces_and_relations() {
+        let service = load(
+            r#"
+namespaces:
+  - name: user
+  - name: document
+    relations:
+      - name: owner
+      - name: viewer
+        rewrite:
+          type: computed_userset
+          relation: owner
+"#,
+        )
+        .unwrap();
+        let ctx = context();
+
+        let viewer = service
+            .get_relation(&ctx, "document", "viewer")
+            .await
+            .unwrap();
+        assert!(matches!(
+            viewer.rewrite(),
+            Rewrite::ComputedUserset { relation } if relation == "owner"
+        ));
+        assert!(matches!(
+            service.get_relation(&ctx, "document", "editor").await,
+            Err(HeimdallError::RelationNotFound { .. })
+        ));
+        assert!(matches!(
+            service.get_namespace(&ctx, "folder").await,
+            Err(HeimdallError::NamespaceNotFound(_))
+        ));
+
+        let names: Vec<String> = service
+            .list_namespaces(&ctx)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|namespace| namespace.name)
+            .collect();
+        assert_eq!(names, ["document", "user"]);
+    }
+
+    #[test]
+    fn rejects_duplicate_declarations() {
+        assert_invalid(
+            r#"
+namespaces:
+  - name: user
+  - name: user
+"#,
+        );
+        assert_invalid(
+            r#"
+namespaces:
+  - name: document
+    relations:
+      - name: owner
+      - name: owner
+"#,
+        );
+    }
+
+    #[test]
+    fn rejects_rewrites_naming_unknown_relations() {
+        assert_invalid(
+            r#"
+namespaces:
+  - name: document
+    relations:
+      - name: viewer
+        rewrite:
+          type: computed_userset
+          relation: owner
+"#,
+        );
+        assert_invalid(
+            r#"
+namespaces:
+  - name: document
+    relations:
+      - name: viewer
+        rewrite:
+          type: exclusion
+          base:
+            type: this
+          subtract:
+            type: union
+            children:
+              - type: tuple_to_userset
+                tupleset: parent
+                computed_userset: viewer
+"#,
+        );
+    }
+
+    #[test]
+    fn leaves_tuple_to_userset_computed_relations_to_check_time() {
+        assert!(
+            load(
+                r#"
+namespaces:
+  - name: document
+    relations:
+      - name: parent
+      - name: viewer
+        rewrite:
+          type: tuple_to_userset
+          tupleset: parent
+          computed_userset: anything
+"#,
+            )
+            .is_ok()
+        );
+    }
+}
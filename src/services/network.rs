@@ -1 +1,115 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::info_span;
+use uuid::Uuid;
 
+use crate::{
+    error::{HeimdallError, HeimdallResult},
+    models::{network::Network, query::TokenPagination, response::PaginatedResponse},
+    persistance::schema::Network as DbNetwork,
+};
+
+use super::traits::NetworkManager;
+
+pub struct NetworkService {
+    pool: PgPool,
+}
+
+impl NetworkService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NetworkManager for NetworkService {
+    async fn create_network(&self, id: Option<Uuid>) -> HeimdallResult<Network> {
+        let span = info_span!("create_network");
+        let _guard = span.enter();
+
+        let id = id.unwrap_or_else(Uuid::new_v4);
+        let now = Utc::now();
+
+        let network: Option<DbNetwork> = sqlx::query_as(
+            "INSERT INTO networks (id, created_at, updated_at) VALUES ($1, $2, $2)
+            ON CONFLICT (id) DO NOTHING
+            RETURNING id, created_at, updated_at",
+        )
+        .bind(id)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        network
+            .map(Into::into)
+            .ok_or(HeimdallError::NetworkAlreadyExists(id))
+    }
+
+    async fn get_network(&self, id: Uuid) -> HeimdallResult<Network> {
+        let span = info_span!("get_network");
+        let _guard = span.enter();
+
+        let network: Option<DbNetwork> =
+            sqlx::query_as("SELECT id, created_at, updated_at FROM networks WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        network
+            .map(Into::into)
+            .ok_or(HeimdallError::NetworkNotFound(id))
+    }
+
+    async fn list_networks(
+        &self,
+        pagination_params: &TokenPagination,
+    ) -> HeimdallResult<PaginatedResponse<Vec<Network>>> {
+        let span = info_span!("list_networks");
+        let _guard = span.enter();
+
+        let limit = pagination_params.page_size.unwrap_or(100);
+
+        let mut networks: Vec<DbNetwork> = sqlx::query_as(
+            "SELECT id, created_at, updated_at FROM networks
+            WHERE id > $1 ORDER BY id LIMIT $2",
+        )
+        .bind(pagination_params.last_id.unwrap_or(Uuid::nil()))
+        .bind(i64::from(limit) + 1)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Same scheme as relation tuples: the extra row only signals another page.
+        let next_page_token = if networks.len() > limit as usize {
+            networks.pop();
+            networks
+                .last()
+                .map(|network| TokenPagination::encode_next_page_token(&network.id))
+                .unwrap_or_else(|| Uuid::nil().to_string())
+        } else {
+            Uuid::nil().to_string()
+        };
+
+        Ok(PaginatedResponse {
+            data: networks.into_iter().map(Into::into).collect(),
+            token: next_page_token,
+        })
+    }
+
+    async fn delete_network(&self, id: Uuid) -> HeimdallResult<()> {
+        let span = info_span!("delete_network");
+        let _guard = span.enter();
+
+        // Relation tuples and UUID mappings go with it through their
+        // `ON DELETE CASCADE` foreign keys.
+        let result = sqlx::query("DELETE FROM networks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(HeimdallError::NetworkNotFound(id));
+        }
+        Ok(())
+    }
+}
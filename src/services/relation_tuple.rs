@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Postgres, QueryBuilder};
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
 use tracing::info_span;
 use uuid::Uuid;
 
@@ -11,23 +13,298 @@ use crate::{
         query::{TokenPagination, relation_tuple::RelationTupleQuery},
         relation_tuple::{RelationTuple, Subject, SubjectID, SubjectSet},
         response::PaginatedResponse,
+        snapshot::SnapshotToken,
     },
-    persistance::schema::RelationTuple as DbRelationTuple,
+    persistance::schema::{RelationTuple as DbRelationTuple, UuidMapping},
 };
 
-use super::traits::RelationTupleManager;
+use super::{
+    traits::{NamespaceManager, RelationTupleManager},
+    uuid_mapper::UuidMappingService,
+};
 
-#[derive(Debug)]
 pub struct RelationTupleService {
     pool: PgPool,
+    namespace_service: Arc<dyn NamespaceManager>,
 }
 
 const CHUNK_SIZE_INSERT_TUPLE: usize = 3000;
 const CHUNK_SIZE_DELETE_TUPLE: usize = 100;
 
+/// Columns copied from a written or deleted tuple into its changelog entry.
+const CHANGE_COLUMNS: &str = "nid, namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation";
+
+/// Tuples split into one array per column, to be bound to `UNNEST`.
+struct TupleColumns {
+    namespaces: Vec<String>,
+    objects: Vec<Uuid>,
+    relations: Vec<String>,
+    subject_ids: Vec<Option<Uuid>>,
+    subject_set_namespaces: Vec<Option<String>>,
+    subject_set_objects: Vec<Option<Uuid>>,
+    subject_set_relations: Vec<Option<String>>,
+}
+
+impl From<&[RelationTuple]> for TupleColumns {
+    fn from(rs: &[RelationTuple]) -> Self {
+        let mut columns = Self {
+            namespaces: Vec::with_capacity(rs.len()),
+            objects: Vec::with_capacity(rs.len()),
+            relations: Vec::with_capacity(rs.len()),
+            subject_ids: Vec::with_capacity(rs.len()),
+            subject_set_namespaces: Vec::with_capacity(rs.len()),
+            subject_set_objects: Vec::with_capacity(rs.len()),
+            subject_set_relations: Vec::with_capacity(rs.len()),
+        };
+
+        for tuple in rs {
+            columns.namespaces.push(tuple.namespace.clone());
+            columns.objects.push(tuple.object);
+            columns.relations.push(tuple.relation.clone());
+            match &tuple.subject {
+                Subject::Direct(SubjectID { id }) => {
+                    columns.subject_ids.push(Some(*id));
+                    columns.subject_set_namespaces.push(None);
+                    columns.subject_set_objects.push(None);
+                    columns.subject_set_relations.push(None);
+                }
+                Subject::Set(SubjectSet {
+                    namespace,
+                    object,
+                    relation,
+                }) => {
+                    columns.subject_ids.push(None);
+                    columns.subject_set_namespaces.push(Some(namespace.clone()));
+                    columns.subject_set_objects.push(Some(*object));
+                    columns.subject_set_relations.push(Some(relation.clone()));
+                }
+            }
+        }
+        columns
+    }
+}
+
 impl RelationTupleService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, namespace_service: Arc<dyn NamespaceManager>) -> Self {
+        Self {
+            pool,
+            namespace_service,
+        }
+    }
+
+    /// Rejects tuples whose namespace or relation is not declared, relations
+    /// whose stored tuples are never read, and subject sets that are unknown or
+    /// not listed among the relation's subject sets.
+    /// A subject set with an empty relation refers to the object itself, as
+    /// used by tuple-to-userset parents.
+    async fn validate_relation_tuple(
+        &self,
+        ctx: &RequestContext,
+        r: &RelationTuple,
+    ) -> HeimdallResult<()> {
+        let invalid = |reason: String| HeimdallError::InvalidRelationTuple {
+            tuple: r.to_string(),
+            reason,
+        };
+
+        let namespace = self
+            .namespace_service
+            .get_namespace(ctx, &r.namespace)
+            .await
+            .map_err(|e| match e {
+                HeimdallError::NamespaceNotFound(name) => {
+                    invalid(format!("unknown namespace {name}"))
+                }
+                e => e,
+            })?;
+        let relation = namespace
+            .relation(&r.relation)
+            .ok_or_else(|| invalid(format!("unknown relation {}#{}", r.namespace, r.relation)))?;
+        if !namespace.reads_stored(relation) {
+            return Err(invalid(format!(
+                "{}#{} is computed and cannot be written",
+                r.namespace, r.relation
+            )));
+        }
+
+        let Subject::Set(ref subject_set) = r.subject else {
+            return Ok(());
+        };
+
+        let subject_namespace = self
+            .namespace_service
+            .get_namespace(ctx, &subject_set.namespace)
+            .await
+            .map_err(|e| match e {
+                HeimdallError::NamespaceNotFound(name) => {
+                    invalid(format!("unknown subject set namespace {name}"))
+                }
+                e => e,
+            })?;
+        if !subject_set.relation.is_empty()
+            && subject_namespace.relation(&subject_set.relation).is_none()
+        {
+            return Err(invalid(format!(
+                "unknown subject set relation {}#{}",
+                subject_set.namespace, subject_set.relation
+            )));
+        }
+        if !relation.allows_subject_set(subject_set) {
+            return Err(invalid(format!(
+                "subject set {}#{} is not allowed on {}#{}",
+                subject_set.namespace, subject_set.relation, r.namespace, r.relation
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the writers of a network by locking its row, so snapshot
+    /// tokens and changelog ids follow commit order within the network. The
+    /// snapshot is only taken once the lock is held.
+    async fn lock_network(
+        conn: &mut PgConnection,
+        ctx: &RequestContext,
+    ) -> HeimdallResult<SnapshotToken> {
+        let locked: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM networks WHERE id = $1 FOR NO KEY UPDATE")
+                .bind(ctx.network_id())
+                .fetch_optional(&mut *conn)
+                .await?;
+        locked.ok_or(HeimdallError::NetworkNotFound(*ctx.network_id()))?;
+
+        // Read in its own statement so the last commit of a writer we waited
+        // on is visible. Taking the database clock rather than ours keeps
+        // tokens comparable across servers, and stepping past the last commit
+        // keeps them increasing when the clock goes backwards.
+        let commit_time: DateTime<Utc> = sqlx::query_scalar(
+            r#"
+            SELECT GREATEST(
+                clock_timestamp(),
+                (SELECT commit_time + INTERVAL '1 microsecond'
+                 FROM heimdall_relation_tuple_changes
+                 WHERE nid = $1
+                 ORDER BY id DESC
+                 LIMIT 1)
+            )
+            "#,
+        )
+        .bind(ctx.network_id())
+        .fetch_one(conn)
+        .await?;
+        Ok(SnapshotToken::from(commit_time))
+    }
+
+    /// Inserts the tuples and their changelog entries at `snapshot`, inside
+    /// the caller's transaction.
+    async fn insert_relation_tuples(
+        conn: &mut PgConnection,
+        ctx: &RequestContext,
+        snapshot: &SnapshotToken,
+        rs: &[RelationTuple],
+    ) -> HeimdallResult<()> {
+        let commit_time = *snapshot.commit_time();
+
+        for rs_chunk in rs.chunks(CHUNK_SIZE_INSERT_TUPLE) {
+            let shard_ids: Vec<Uuid> = (0..rs_chunk.len()).map(|_| Uuid::new_v4()).collect();
+            let nids = vec![*ctx.network_id(); rs_chunk.len()];
+            let TupleColumns {
+                namespaces,
+                objects,
+                relations,
+                subject_ids,
+                subject_set_namespaces,
+                subject_set_objects,
+                subject_set_relations,
+            } = TupleColumns::from(rs_chunk);
+            let commit_times: Vec<DateTime<Utc>> = vec![commit_time; rs_chunk.len()];
+
+            sqlx::query(&format!(
+                "WITH inserted AS (
+                    INSERT INTO heimdall_relation_tuples
+                    (shard_id, nid, namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation, commit_time)
+                    SELECT * FROM UNNEST ($1::UUID[], $2::UUID[], $3::VARCHAR[], $4::UUID[], $5::VARCHAR[], $6::UUID[], $7::VARCHAR[], $8::UUID[], $9::VARCHAR[], $10::TIMESTAMPTZ[])
+                    RETURNING {CHANGE_COLUMNS}, commit_time
+                )
+                INSERT INTO heimdall_relation_tuple_changes ({CHANGE_COLUMNS}, commit_time, action)
+                SELECT {CHANGE_COLUMNS}, commit_time, 'insert' FROM inserted",
+            ))
+                .bind(shard_ids)
+                .bind(nids)
+                .bind(namespaces)
+                .bind(objects)
+                .bind(relations)
+                .bind(subject_ids)
+                .bind(subject_set_namespaces)
+                .bind(subject_set_objects)
+                .bind(subject_set_relations)
+                .bind(commit_times)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| match e {
+                    // The only foreign key is the one to `networks`.
+                    sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                        HeimdallError::NetworkNotFound(*ctx.network_id())
+                    }
+                    e => e.into(),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the tuples and records their changelog entries at `snapshot`,
+    /// inside the caller's transaction.
+    async fn remove_relation_tuples(
+        conn: &mut PgConnection,
+        ctx: &RequestContext,
+        snapshot: &SnapshotToken,
+        rs: &[RelationTuple],
+    ) -> HeimdallResult<()> {
+        for rs_chunk in rs.chunks(CHUNK_SIZE_DELETE_TUPLE) {
+            let TupleColumns {
+                namespaces,
+                objects,
+                relations,
+                subject_ids,
+                subject_set_namespaces,
+                subject_set_objects,
+                subject_set_relations,
+            } = TupleColumns::from(rs_chunk);
+            let nids = vec![ctx.network_id(); rs_chunk.len()];
+
+            sqlx::query(&format!("WITH deleted AS (
+                        DELETE FROM heimdall_relation_tuples t
+                        USING UNNEST($1::VARCHAR[], $2::UUID[], $3::VARCHAR[], $4::UUID[], $5::VARCHAR[], $6::UUID[], $7::VARCHAR[], $8::UUID[])
+                        AS u(namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation, nid)
+                        WHERE
+                        t.namespace = u.namespace AND
+                        t.object = u.object AND
+                        t.relation = u.relation AND
+                        t.subject_id IS NOT DISTINCT FROM u.subject_id AND
+                        t.subject_set_namespace IS NOT DISTINCT FROM u.subject_set_namespace AND
+                        t.subject_set_object IS NOT DISTINCT FROM u.subject_set_object AND
+                        t.subject_set_relation IS NOT DISTINCT FROM u.subject_set_relation AND
+                        t.nid = u.nid
+                        RETURNING t.*
+                        )
+                        INSERT INTO heimdall_relation_tuple_changes ({CHANGE_COLUMNS}, commit_time, action)
+                        SELECT {CHANGE_COLUMNS}, $9, 'delete' FROM deleted
+                        "))
+                .bind(namespaces)
+                .bind(objects)
+                .bind(relations)
+                .bind(subject_ids)
+                .bind(subject_set_namespaces)
+                .bind(subject_set_objects)
+                .bind(subject_set_relations)
+                .bind(nids)
+                .bind(snapshot.commit_time())
+                .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
     }
 
     fn with_network<'a>(builder: &mut QueryBuilder<'a, Postgres>, ctx: &'a RequestContext) {
@@ -35,6 +312,14 @@ impl RelationTupleService {
         builder.push_bind(ctx.network_id());
     }
 
+    /// Hides tuples committed after the snapshot the context reads at.
+    fn with_snapshot<'a>(builder: &mut QueryBuilder<'a, Postgres>, ctx: &'a RequestContext) {
+        if let Some(snapshot) = ctx.consistency().snapshot() {
+            builder.push(" AND commit_time <= ");
+            builder.push_bind(snapshot);
+        }
+    }
+
     fn with_query_filters<'a>(
         builder: &mut QueryBuilder<'a, Postgres>,
         rs_query: &'a RelationTupleQuery,
@@ -73,7 +358,7 @@ impl RelationTupleService {
                 builder.push(" AND subject_set_object = ");
                 builder.push_bind(*object);
                 builder.push(" AND subject_set_relation = ");
-                builder.push(relation);
+                builder.push_bind(relation);
                 builder.push(" AND subject_id IS NULL");
             }
         }
@@ -86,7 +371,8 @@ impl RelationTupleManager for RelationTupleService {
         &self,
         ctx: &RequestContext,
         rs: &[RelationTuple],
-    ) -> HeimdallResult<()> {
+        mappings: &[UuidMapping],
+    ) -> HeimdallResult<SnapshotToken> {
         if rs.is_empty() {
             return Err(HeimdallError::MalformedInput);
         }
@@ -94,68 +380,17 @@ impl RelationTupleManager for RelationTupleService {
         let span = info_span!("write_relation_tuples", relation_tuple_count = rs.len());
         let _guard = span.enter();
 
-        let commit_time = Utc::now();
+        for r in rs {
+            self.validate_relation_tuple(ctx, r).await?;
+        }
 
         let mut tx = self.pool.begin().await?;
-
-        for rs_chunk in rs.chunks(CHUNK_SIZE_INSERT_TUPLE) {
-            let shard_ids: Vec<Uuid> = vec![Uuid::new_v4(); rs_chunk.len()];
-            let nids = vec![*ctx.network_id(); rs_chunk.len()];
-            let mut namespaces: Vec<String> = Vec::with_capacity(rs_chunk.len());
-            let mut objects: Vec<Uuid> = Vec::with_capacity(rs_chunk.len());
-            let mut relations: Vec<String> = Vec::with_capacity(rs_chunk.len());
-            let mut subject_ids: Vec<Option<Uuid>> = Vec::with_capacity(rs_chunk.len());
-            let mut subject_set_namespaces: Vec<Option<String>> =
-                Vec::with_capacity(rs_chunk.len());
-            let mut subject_set_objects: Vec<Option<Uuid>> = Vec::with_capacity(rs_chunk.len());
-            let mut subject_set_relations: Vec<Option<String>> = Vec::with_capacity(rs_chunk.len());
-            let commit_times: Vec<DateTime<Utc>> = vec![commit_time; rs_chunk.len()];
-            for r in rs_chunk {
-                namespaces.push(r.namespace.clone());
-                objects.push(r.object);
-                relations.push(r.relation.clone());
-                match &r.subject {
-                    Subject::Direct(SubjectID { id }) => {
-                        subject_ids.push(Some(*id));
-                        subject_set_namespaces.push(None);
-                        subject_set_objects.push(None);
-                        subject_set_relations.push(None);
-                    }
-                    Subject::Set(SubjectSet {
-                        namespace,
-                        object,
-                        relation,
-                    }) => {
-                        subject_ids.push(None);
-                        subject_set_namespaces.push(Some(namespace.clone()));
-                        subject_set_objects.push(Some(*object));
-                        subject_set_relations.push(Some(relation.clone()));
-                    }
-                }
-            }
-
-            sqlx::query(
-                "INSERT INTO heimdall_relation_tuples 
-                (shard_id, nid, namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation, commit_time)
-                SELECT * FROM UNNEST 
-                ($1::UUID[], $2::UUID[], $3::VARCHAR[], $4::UUID[], $5::VARCHAR[], $6::UUID[], $7, $8, $9 SELECT * FROM UNNEST ($1::UUID[], $2::UUID[], $3::VARCHAR[], $4::UUID[], $5::VARCHAR[], $6::UUID[], $7::VARCHAR[], $8::UUID[], $9::VARCHAR[], $10::TIMESTAMPTZ[])",
-            )
-                .bind(shard_ids)
-                .bind(nids)
-                .bind(namespaces)
-                .bind(objects)
-                .bind(relations)
-                .bind(subject_ids)
-                .bind(subject_set_namespaces)
-                .bind(subject_set_objects)
-                .bind(subject_set_relations)
-                .bind(commit_times)
-                .execute(&mut *tx)
-                .await?;
-        }
+        let snapshot = Self::lock_network(&mut tx, ctx).await?;
+        UuidMappingService::insert_mappings(&mut tx, ctx, mappings).await?;
+        Self::insert_relation_tuples(&mut tx, ctx, &snapshot, rs).await?;
 
         tx.commit().await?;
-        Ok(())
+        Ok(snapshot)
     }
 
     async fn get_relation_tuples(
@@ -179,23 +414,29 @@ impl RelationTupleManager for RelationTupleService {
                 subject_id,
                 subject_set_namespace,
                 subject_set_object,
-                subject_set_relation
+                subject_set_relation,
                 commit_time
             FROM heimdall_relation_tuples WHERE ",
         );
         Self::with_network(&mut builder, ctx);
+        Self::with_snapshot(&mut builder, ctx);
         Self::with_query_filters(&mut builder, rs_query);
         builder.push(" AND shard_id > ");
-        builder.push_bind(pagination_params.last_id);
-        builder.push("ORDER BY shard_id LIMIT ");
+        builder.push_bind(pagination_params.last_id.unwrap_or(Uuid::nil()));
+        builder.push(" ORDER BY shard_id LIMIT ");
         builder.push(limit + 1);
 
         let mut query_result: Vec<DbRelationTuple> =
             builder.build_query_as().fetch_all(&self.pool).await?;
 
+        // The extra row only signals that another page exists; the next page
+        // starts after the last row that is actually returned.
         let next_page_token = if query_result.len() > limit as usize {
-            let extra_row = query_result.pop().unwrap();
-            TokenPagination::encode_next_page_token(&extra_row.shard_id)
+            query_result.pop();
+            query_result
+                .last()
+                .map(|row| TokenPagination::encode_next_page_token(&row.shard_id))
+                .unwrap_or_else(|| Uuid::nil().to_string())
         } else {
             Uuid::nil().to_string()
         };
@@ -216,114 +457,210 @@ impl RelationTupleManager for RelationTupleService {
         let span = info_span!("exists_relation_tuples");
         let _guard = span.enter();
 
-        let mut exists_query_builder =
-            QueryBuilder::new("SELECT 1 FROM heimdall_relation_tuples WHERE");
-        Self::with_network(&mut exists_query_builder, ctx);
-        Self::with_query_filters(&mut exists_query_builder, rs_query);
-        let mut builder = QueryBuilder::new("SELECT EXISTS (");
-        builder.push(exists_query_builder.sql());
+        let mut builder =
+            QueryBuilder::new("SELECT EXISTS (SELECT 1 FROM heimdall_relation_tuples WHERE");
+        Self::with_network(&mut builder, ctx);
+        Self::with_snapshot(&mut builder, ctx);
+        Self::with_query_filters(&mut builder, rs_query);
         builder.push(")");
         let exists: bool = builder.build_query_scalar().fetch_one(&self.pool).await?;
         Ok(exists)
     }
 
+    async fn exists_relation_tuples_batch(
+        &self,
+        ctx: &RequestContext,
+        rs: &[RelationTuple],
+    ) -> HeimdallResult<Vec<bool>> {
+        if rs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let span = info_span!("exists_relation_tuples_batch", count = rs.len());
+        let _guard = span.enter();
+
+        let TupleColumns {
+            namespaces,
+            objects,
+            relations,
+            subject_ids,
+            subject_set_namespaces,
+            subject_set_objects,
+            subject_set_relations,
+        } = TupleColumns::from(rs);
+
+        let exists: Vec<bool> = sqlx::query_scalar(
+            "SELECT EXISTS (
+                    SELECT 1 FROM heimdall_relation_tuples t
+                    WHERE
+                    t.nid = $8 AND
+                    ($9::TIMESTAMPTZ IS NULL OR t.commit_time <= $9) AND
+                    t.namespace = u.namespace AND
+                    t.object = u.object AND
+                    t.relation = u.relation AND
+                    t.subject_id IS NOT DISTINCT FROM u.subject_id AND
+                    t.subject_set_namespace IS NOT DISTINCT FROM u.subject_set_namespace AND
+                    t.subject_set_object IS NOT DISTINCT FROM u.subject_set_object AND
+                    t.subject_set_relation IS NOT DISTINCT FROM u.subject_set_relation
+                )
+                FROM UNNEST($1::VARCHAR[], $2::UUID[], $3::VARCHAR[], $4::UUID[], $5::VARCHAR[], $6::UUID[], $7::VARCHAR[])
+                WITH ORDINALITY
+                AS u(namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation, position)
+                ORDER BY u.position",
+        )
+        .bind(namespaces)
+        .bind(objects)
+        .bind(relations)
+        .bind(subject_ids)
+        .bind(subject_set_namespaces)
+        .bind(subject_set_objects)
+        .bind(subject_set_relations)
+        .bind(ctx.network_id())
+        .bind(ctx.consistency().snapshot())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(exists)
+    }
+
     async fn delete_relation_tuples(
         &self,
         ctx: &RequestContext,
         rs: &[RelationTuple],
-    ) -> HeimdallResult<()> {
+    ) -> HeimdallResult<SnapshotToken> {
         if rs.is_empty() {
-            return Ok(());
+            let now: DateTime<Utc> = sqlx::query_scalar("SELECT clock_timestamp()")
+                .fetch_one(&self.pool)
+                .await?;
+            return Ok(SnapshotToken::from(now));
         }
 
         let span = info_span!("delete_relation_tuples", count = rs.len());
         let _guard = span.enter();
 
         let mut tx = self.pool.begin().await?;
+        let snapshot = Self::lock_network(&mut tx, ctx).await?;
+        Self::remove_relation_tuples(&mut tx, ctx, &snapshot, rs).await?;
 
-        for rs_chunk in rs.chunks(CHUNK_SIZE_DELETE_TUPLE) {
-            let mut namespaces: Vec<String> = Vec::with_capacity(rs_chunk.len());
-            let mut objects: Vec<Uuid> = Vec::with_capacity(rs_chunk.len());
-            let mut relations: Vec<String> = Vec::with_capacity(rs_chunk.len());
-            let mut subject_ids: Vec<Option<Uuid>> = Vec::with_capacity(rs_chunk.len());
-            let mut subject_set_namespaces: Vec<Option<String>> =
-                Vec::with_capacity(rs_chunk.len());
-            let mut subject_set_objects: Vec<Option<Uuid>> = Vec::with_capacity(rs_chunk.len());
-            let mut subject_set_relations: Vec<Option<String>> = Vec::with_capacity(rs_chunk.len());
-            let nids = vec![ctx.network_id(); rs_chunk.len()];
+        // NOTE: if the transaction is not commited, it rolls back automatically
+        tx.commit().await?;
+        Ok(snapshot)
+    }
 
-            for tuple in rs_chunk {
-                namespaces.push(tuple.namespace.clone());
-                objects.push(tuple.object);
-                relations.push(tuple.relation.clone());
-                match &tuple.subject {
-                    Subject::Direct(SubjectID { id }) => {
-                        subject_ids.push(Some(*id));
-                        subject_set_namespaces.push(None);
-                        subject_set_objects.push(None);
-                        subject_set_relations.push(None);
-                    }
-                    Subject::Set(SubjectSet {
-                        namespace,
-                        object,
-                        relation,
-                    }) => {
-                        subject_ids.push(None);
-                        subject_set_namespaces.push(Some(namespace.clone()));
-                        subject_set_objects.push(Some(*object));
-                        subject_set_relations.push(Some(relation.clone()));
-                    }
-                }
-            }
+    async fn transact_relation_tuples(
+        &self,
+        ctx: &RequestContext,
+        inserts: &[RelationTuple],
+        deletes: &[RelationTuple],
+        mappings: &[UuidMapping],
+    ) -> HeimdallResult<SnapshotToken> {
+        if inserts.is_empty() && deletes.is_empty() {
+            return Err(HeimdallError::MalformedInput);
+        }
 
-            sqlx::query("DELETE FROM heimdall_relation_tuples t
-                        USING UNNEST($1::VARCHAR[], $2::UUID[], $3::VARCHAR[], $4::UUID[], $5::VARCHAR[], $6::UUID[], $7::VARCHAR[], $8::UUID[])
-                        AS u(namespace, object, relation, subject_id, subject_set_namespace, subject_set_object, subject_set_relation, nid)
-                        WHERE 
-                        t.namespace = u.namespace AND
-                        t.object = u.object AND 
-                        t.relation = u.relation AND
-                        t.subject_id = u.subject_id AND
-                        t.subject_set_namespace = u.subject_set_namespace AND
-                        t.subject_set_object = u.subject_set_object AND
-                        t.subject_set_relation = u.subject_set_relation AND 
-                        t.nid = u.nid
-                        ")
-                .bind(namespaces)
-                .bind(objects)
-                .bind(relations)
-                .bind(subject_ids)
-                .bind(subject_set_namespaces)
-                .bind(subject_set_objects)
-                .bind(subject_set_relations)
-                .bind(nids)
-                .execute(&mut *tx)
-            .await?;
+        let span = info_span!(
+            "transact_relation_tuples",
+            insert_count = inserts.len(),
+            delete_count = deletes.len()
+        );
+        let _guard = span.enter();
+
+        for r in inserts {
+            self.validate_relation_tuple(ctx, r).await?;
         }
 
-        // NOTE: if the transaction is not commited, it rolls back automatically
+        let mut tx = self.pool.begin().await?;
+        let snapshot = Self::lock_network(&mut tx, ctx).await?;
+        // Deleting first lets a patch replace a tuple with itself, which then
+        // ends up present.
+        UuidMappingService::insert_mappings(&mut tx, ctx, mappings).await?;
+        Self::remove_relation_tuples(&mut tx, ctx, &snapshot, deletes).await?;
+        Self::insert_relation_tuples(&mut tx, ctx, &snapshot, inserts).await?;
+
         tx.commit().await?;
-        Ok(())
+        Ok(snapshot)
     }
 
     async fn delete_all_relation_tuples(
         &self,
         ctx: &RequestContext,
         rs_query: &RelationTupleQuery,
-    ) -> HeimdallResult<()> {
+    ) -> HeimdallResult<SnapshotToken> {
         let span = info_span!("delete_relation_tuples");
         let _guard = span.enter();
 
         let mut tx = self.pool.begin().await?;
+        let snapshot = Self::lock_network(&mut tx, ctx).await?;
 
-        let mut builder = QueryBuilder::new("DELETE FROM heimdall_relation_tuples WHERE ");
+        let mut builder =
+            QueryBuilder::new("WITH deleted AS (DELETE FROM heimdall_relation_tuples WHERE ");
 
         Self::with_network(&mut builder, ctx);
         Self::with_query_filters(&mut builder, rs_query);
+        builder.push(format!(
+            " RETURNING *) INSERT INTO heimdall_relation_tuple_changes ({CHANGE_COLUMNS}, commit_time, action) SELECT {CHANGE_COLUMNS}, "
+        ));
+        builder.push_bind(snapshot.commit_time());
+        builder.push(", 'delete' FROM deleted");
 
         builder.build().execute(&mut *tx).await?;
 
         tx.commit().await?;
-        Ok(())
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::{
+        memory::{context, namespaces, tuple},
+        namespace::NamespaceService,
+    };
+
+    use super::*;
+
+    const NAMESPACES: &str = r#"
+namespaces:
+  - name: user
+  - name: folder
+  - name: document
+    relations:
+      - name: owner
+      - name: parent
+        subject_sets:
+          - namespace: folder
+      - name: viewer
+        rewrite:
+          type: tuple_to_userset
+          tupleset: parent
+          computed_userset: viewer
+"#;
+
+    fn service() -> RelationTupleService {
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let namespace_service = NamespaceService::new(namespaces(NAMESPACES)).unwrap();
+        RelationTupleService::new(pool, Arc::new(namespace_service))
+    }
+
+    async fn validate(s: &str) -> HeimdallResult<()> {
+        service()
+            .validate_relation_tuple(&context(), &tuple(s))
+            .await
+    }
+
+    #[tokio::test]
+    async fn object_only_subject_sets_need_to_be_listed() {
+        assert!(validate("document:d#parent@folder:f#").await.is_ok());
+        assert!(matches!(
+            validate("document:d#owner@folder:f#").await,
+            Err(HeimdallError::InvalidRelationTuple { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn computed_relations_cannot_be_written() {
+        assert!(matches!(
+            validate("document:d#viewer@user").await,
+            Err(HeimdallError::InvalidRelationTuple { .. })
+        ));
     }
 }
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::api_key::{ApiKey, ApiKeyScope, CreatedApiKey},
+};
+
+#[async_trait]
+#[allow(unused)]
+pub trait ApiKeyManager: Send + Sync {
+    /// Creates a key for the context's network. The returned secret is not
+    /// stored and cannot be retrieved later.
+    async fn create_api_key(
+        &self,
+        ctx: &RequestContext,
+        scope: ApiKeyScope,
+        name: &str,
+    ) -> HeimdallResult<CreatedApiKey>;
+
+    async fn list_api_keys(&self, ctx: &RequestContext) -> HeimdallResult<Vec<ApiKey>>;
+
+    async fn delete_api_key(&self, ctx: &RequestContext, id: Uuid) -> HeimdallResult<()>;
+
+    /// Looks up the key matching `secret`. Runs before a request context
+    /// exists, since the key decides the network.
+    async fn authenticate(&self, secret: &str) -> HeimdallResult<Option<ApiKey>>;
+}
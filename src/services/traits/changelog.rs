@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::{change::RelationTupleChange, snapshot::SnapshotToken},
+};
+
+/// Reads the log of relation tuple inserts and deletes. Entries are written
+/// by the `RelationTupleManager` in the same transaction as the change.
+#[async_trait]
+#[allow(unused)]
+pub trait ChangelogManager: Send + Sync {
+    /// Id of the network's last change committed at or before `snapshot`, or
+    /// of its last change so far when `None`. Zero when there is none.
+    async fn change_cursor(
+        &self,
+        ctx: &RequestContext,
+        snapshot: Option<&SnapshotToken>,
+    ) -> HeimdallResult<i64>;
+
+    /// Up to `limit` of the network's changes after `cursor`, oldest first.
+    async fn list_changes(
+        &self,
+        ctx: &RequestContext,
+        cursor: i64,
+        limit: i64,
+    ) -> HeimdallResult<Vec<RelationTupleChange>>;
+
+    /// Commit time of the network's most recent delete, if any.
+    async fn last_delete(&self, ctx: &RequestContext) -> HeimdallResult<Option<DateTime<Utc>>>;
+}
@@ -1,7 +1,15 @@
+mod api_key;
+mod changelog;
+mod namespace;
+mod network;
 mod relation_tuple;
 mod traversal;
 mod uuid_mapping;
 
+pub use self::api_key::ApiKeyManager;
+pub use self::changelog::ChangelogManager;
+pub use self::namespace::NamespaceManager;
+pub use self::network::NetworkManager;
 pub use self::relation_tuple::RelationTupleManager;
 pub use self::traversal::TraversalManager;
 pub use self::uuid_mapping::UuidMappingManager;
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use crate::{
+    context::RequestContext,
+    error::HeimdallResult,
+    models::namespace::{Namespace, Relation},
+};
+
+#[async_trait]
+#[allow(unused)]
+pub trait NamespaceManager: Send + Sync {
+    async fn get_namespace(&self, ctx: &RequestContext, name: &str) -> HeimdallResult<Namespace>;
+
+    async fn get_relation(
+        &self,
+        ctx: &RequestContext,
+        namespace: &str,
+        relation: &str,
+    ) -> HeimdallResult<Relation>;
+
+    async fn list_namespaces(&self, ctx: &RequestContext) -> HeimdallResult<Vec<Namespace>>;
+}
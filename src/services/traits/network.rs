@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    error::HeimdallResult,
+    models::{network::Network, query::TokenPagination, response::PaginatedResponse},
+};
+
+/// Manages the networks themselves. Unlike the other managers it takes no
+/// `RequestContext`, since a network is what a request context is scoped to.
+#[async_trait]
+#[allow(unused)]
+pub trait NetworkManager: Send + Sync {
+    /// Creates a network with `id`, or a random one when `None`.
+    async fn create_network(&self, id: Option<Uuid>) -> HeimdallResult<Network>;
+
+    async fn get_network(&self, id: Uuid) -> HeimdallResult<Network>;
+
+    async fn list_networks(
+        &self,
+        pagination_params: &TokenPagination,
+    ) -> HeimdallResult<PaginatedResponse<Vec<Network>>>;
+
+    /// Deletes the network along with its relation tuples and UUID mappings.
+    async fn delete_network(&self, id: Uuid) -> HeimdallResult<()>;
+}
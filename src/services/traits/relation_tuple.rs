@@ -7,9 +7,15 @@ use crate::{
         query::{TokenPagination, relation_tuple::RelationTupleQuery},
         relation_tuple::RelationTuple,
         response::PaginatedResponse,
+        snapshot::SnapshotToken,
     },
+    persistance::schema::UuidMapping,
 };
 
+/// Writes return the snapshot they were committed at, and store the `mappings`
+/// of the string IDs they were sent in the same transaction. Reads only see
+/// tuples committed at or before the snapshot of the context's consistency, if
+/// any.
 #[async_trait]
 #[allow(unused)]
 pub trait RelationTupleManager: Send + Sync {
@@ -17,7 +23,8 @@ pub trait RelationTupleManager: Send + Sync {
         &self,
         ctx: &RequestContext,
         rs: &[RelationTuple],
-    ) -> HeimdallResult<()>;
+        mappings: &[UuidMapping],
+    ) -> HeimdallResult<SnapshotToken>;
 
     async fn get_relation_tuples(
         &self,
@@ -32,15 +39,33 @@ pub trait RelationTupleManager: Send + Sync {
         rs_query: &RelationTupleQuery,
     ) -> HeimdallResult<bool>;
 
+    /// Tells for each tuple whether it is stored as-is, in the order given,
+    /// with one query.
+    async fn exists_relation_tuples_batch(
+        &self,
+        ctx: &RequestContext,
+        rs: &[RelationTuple],
+    ) -> HeimdallResult<Vec<bool>>;
+
     async fn delete_relation_tuples(
         &self,
         ctx: &RequestContext,
         rs: &[RelationTuple],
-    ) -> HeimdallResult<()>;
+    ) -> HeimdallResult<SnapshotToken>;
+
+    /// Applies the deletes and then the inserts in one transaction, so readers
+    /// see either none or all of them.
+    async fn transact_relation_tuples(
+        &self,
+        ctx: &RequestContext,
+        inserts: &[RelationTuple],
+        deletes: &[RelationTuple],
+        mappings: &[UuidMapping],
+    ) -> HeimdallResult<SnapshotToken>;
 
     async fn delete_all_relation_tuples(
         &self,
         ctx: &RequestContext,
         rs_query: &RelationTupleQuery,
-    ) -> HeimdallResult<()>;
+    ) -> HeimdallResult<SnapshotToken>;
 }
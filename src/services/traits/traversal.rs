@@ -3,12 +3,18 @@ use async_trait::async_trait;
 use crate::{
     context::RequestContext,
     error::HeimdallResult,
-    models::{relation_tuple::RelationTuple, traversal::TraversalResult},
+    models::{
+        relation_tuple::{RelationTuple, Subject, SubjectSet},
+        traversal::TraversalResult,
+    },
 };
 
 #[async_trait]
 #[allow(unused)]
 pub trait TraversalManager: Send + Sync {
+    /// Follows the subject sets stored on the start tuple's relation. Every
+    /// subject set is returned, also after one found as-is, since whether a
+    /// stored match decides depends on the target relation's rewrite.
     async fn traverse_subject_set_expansion(
         &self,
         ctx: &RequestContext,
@@ -20,4 +26,29 @@ pub trait TraversalManager: Send + Sync {
         start: &RelationTuple,
         computed_subject_sets: &[String],
     ) -> HeimdallResult<Vec<TraversalResult>>;
+    /// Follows the subject sets stored under `tupleset_relation` on the start
+    /// object (e.g. `document#parent -> folder:a`) and checks
+    /// `computed_relation` on each of them (e.g. `folder:a#viewer`).
+    async fn traverse_tuple_to_userset(
+        &self,
+        ctx: &RequestContext,
+        start: &RelationTuple,
+        tupleset_relation: &str,
+        computed_relation: &str,
+    ) -> HeimdallResult<Vec<TraversalResult>>;
+    /// Lists the subjects stored on `subject_set`, e.g. the members of
+    /// `group:a#member`, without following nested subject sets.
+    async fn traverse_subject_set_members(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+    ) -> HeimdallResult<Vec<Subject>>;
+    /// Lists the tuples pointing at `subject`, walking edges backwards. A
+    /// subject set matches on its object alone, whatever relation the tuples
+    /// name, the way tuple-to-userset parents are followed.
+    async fn traverse_reverse(
+        &self,
+        ctx: &RequestContext,
+        subject: &Subject,
+    ) -> HeimdallResult<Vec<RelationTuple>>;
 }
@@ -6,18 +6,16 @@ use crate::{context::RequestContext, error::HeimdallResult, models::query::Token
 #[async_trait]
 #[allow(unused)]
 pub trait UuidMappingManager: Send + Sync {
-    async fn map_strings_to_uuids(
-        &self,
-        ctx: &RequestContext,
-        values: &[String],
-    ) -> HeimdallResult<Vec<Uuid>>;
-
+    /// The UUID each string maps to. Writes store the mappings along with the
+    /// tuples, see `UuidMappingService::insert_mappings`.
     async fn map_strings_to_uuids_readonly(
         &self,
         ctx: &RequestContext,
         values: &[String],
     ) -> HeimdallResult<Vec<Uuid>>;
 
+    /// Returns the string each ID was mapped from, or the ID itself when it
+    /// has no mapping.
     async fn map_uuids_to_strings(
         &self,
         ctx: &RequestContext,
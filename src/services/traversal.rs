@@ -10,7 +10,9 @@ use crate::{
         relation_tuple::{RelationTuple, Subject, SubjectID, SubjectSet},
         traversal::{Traversal, TraversalResult},
     },
-    persistance::schema::SubjectExapandedRelationTupleRow,
+    persistance::schema::{
+        RelationTuple as DbRelationTuple, SubjectExapandedRelationTupleRow, SubjectSetRewriteRow,
+    },
 };
 
 use super::traits::TraversalManager;
@@ -26,6 +28,24 @@ impl TraversalService {
         Self { pool }
     }
 
+    /// Hides tuples committed after the snapshot the context reads at. `table`
+    /// qualifies the column when the outer and nested queries both need it.
+    fn with_snapshot<'a>(
+        builder: &mut QueryBuilder<'a, Postgres>,
+        ctx: &'a RequestContext,
+        table: Option<&str>,
+    ) {
+        if let Some(snapshot) = ctx.consistency().snapshot() {
+            builder.push(" AND ");
+            if let Some(table) = table {
+                builder.push(table);
+                builder.push(".");
+            }
+            builder.push("commit_time <= ");
+            builder.push_bind(snapshot);
+        }
+    }
+
     fn with_subject_filter<'a>(builder: &mut QueryBuilder<'a, Postgres>, subject: &'a Subject) {
         match subject {
             Subject::Direct(SubjectID { id }) => {
@@ -60,26 +80,23 @@ impl TraversalManager for TraversalService {
         let span = info_span!("traverse_subject_set_expansion");
         let _guard = span.enter();
 
-        let mut exists_builder = QueryBuilder::new(
-            "SELECT 1 FROM heimdall_relation_tuples WHERE nid = current.nid AND namespace = current.subject_set_namespace AND relation = current.subject_set_relation AND ",
-        );
-        Self::with_subject_filter(&mut exists_builder, &start.subject);
-
         let mut shard_id = Uuid::nil();
         let mut results = Vec::new();
 
         loop {
             let mut builder = QueryBuilder::new(
                 r#"SELECT current.shard_id AS shard_id,
-                          current.subject_set_namespace as namespace,
-                          current.subject_set_object as object,
-                          current.subject_set_relation as relation,
-                          EXISTS ("#,
+                          current.subject_set_namespace AS subject_set_namespace,
+                          current.subject_set_object AS subject_set_object,
+                          current.subject_set_relation AS subject_set_relation,
+                          EXISTS (SELECT 1 FROM heimdall_relation_tuples WHERE nid = current.nid AND namespace = current.subject_set_namespace AND object = current.subject_set_object AND relation = current.subject_set_relation AND"#,
             );
-            builder.push(exists_builder.sql());
+            Self::with_subject_filter(&mut builder, &start.subject);
+            Self::with_snapshot(&mut builder, ctx, None);
             builder.push(") AS found FROM heimdall_relation_tuples AS current WHERE");
             builder.push(" current.nid = ");
             builder.push_bind(ctx.network_id());
+            Self::with_snapshot(&mut builder, ctx, Some("current"));
             builder.push(" AND current.shard_id > ");
             builder.push_bind(shard_id);
             builder.push(" AND current.namespace = ");
@@ -115,10 +132,6 @@ impl TraversalManager for TraversalService {
                 };
 
                 results.push(result);
-
-                if row.found {
-                    return Ok(results);
-                }
                 shard_id = row.shard_id;
             }
 
@@ -131,10 +144,231 @@ impl TraversalManager for TraversalService {
 
     async fn traverse_subject_set_rewrite(
         &self,
-        _ctx: &RequestContext,
-        _start: &RelationTuple,
-        _computed_suject_set: &[String],
+        ctx: &RequestContext,
+        start: &RelationTuple,
+        computed_subject_sets: &[String],
+    ) -> HeimdallResult<Vec<TraversalResult>> {
+        if computed_subject_sets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let span = info_span!(
+            "traverse_subject_set_rewrite",
+            computed_subject_set_count = computed_subject_sets.len()
+        );
+        let _guard = span.enter();
+
+        let mut builder = QueryBuilder::new(
+            "SELECT computed.relation AS relation, EXISTS (SELECT 1 FROM heimdall_relation_tuples WHERE nid = ",
+        );
+        builder.push_bind(ctx.network_id());
+        builder.push(" AND namespace = ");
+        builder.push_bind(start.namespace.clone());
+        builder.push(" AND object = ");
+        builder.push_bind(start.object);
+        builder.push(" AND relation = computed.relation AND");
+        Self::with_subject_filter(&mut builder, &start.subject);
+        Self::with_snapshot(&mut builder, ctx, None);
+        builder.push(") AS found FROM UNNEST(");
+        builder.push_bind(computed_subject_sets.to_vec());
+        builder.push("::VARCHAR[]) WITH ORDINALITY AS computed(relation, position) ORDER BY computed.position");
+
+        let rows: Vec<SubjectSetRewriteRow> =
+            builder.build_query_as().fetch_all(&self.pool).await?;
+
+        let mut results = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let to = RelationTuple {
+                namespace: start.namespace.clone(),
+                object: start.object,
+                relation: row.relation,
+                subject: start.subject.clone(),
+            };
+            results.push(TraversalResult {
+                from: start.clone(),
+                to,
+                via: Traversal::ComputedUserset,
+                found: row.found,
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn traverse_tuple_to_userset(
+        &self,
+        ctx: &RequestContext,
+        start: &RelationTuple,
+        tupleset_relation: &str,
+        computed_relation: &str,
     ) -> HeimdallResult<Vec<TraversalResult>> {
-        todo!()
+        let span = info_span!(
+            "traverse_tuple_to_userset",
+            tupleset_relation = tupleset_relation,
+            computed_relation = computed_relation
+        );
+        let _guard = span.enter();
+
+        let mut shard_id = Uuid::nil();
+        let mut results = Vec::new();
+
+        loop {
+            let mut builder = QueryBuilder::new(
+                r#"SELECT current.shard_id AS shard_id,
+                          current.subject_set_namespace AS subject_set_namespace,
+                          current.subject_set_object AS subject_set_object,
+                          current.subject_set_relation AS subject_set_relation,
+                          EXISTS (SELECT 1 FROM heimdall_relation_tuples WHERE nid = current.nid AND namespace = current.subject_set_namespace AND object = current.subject_set_object AND relation = "#,
+            );
+            builder.push_bind(computed_relation.to_string());
+            builder.push(" AND");
+            Self::with_subject_filter(&mut builder, &start.subject);
+            Self::with_snapshot(&mut builder, ctx, None);
+            builder.push(") AS found FROM heimdall_relation_tuples AS current WHERE");
+            builder.push(" current.nid = ");
+            builder.push_bind(ctx.network_id());
+            Self::with_snapshot(&mut builder, ctx, Some("current"));
+            builder.push(" AND current.shard_id > ");
+            builder.push_bind(shard_id);
+            builder.push(" AND current.namespace = ");
+            builder.push_bind(start.namespace.clone());
+            builder.push(" AND current.object = ");
+            builder.push_bind(start.object);
+            builder.push(" AND current.relation = ");
+            builder.push_bind(tupleset_relation.to_string());
+            builder.push(" AND current.subject_id IS NULL");
+            builder.push(" ORDER BY current.shard_id");
+            builder.push(" LIMIT ");
+            builder.push_bind(QUERY_LIMIT);
+
+            let rows: Vec<SubjectExapandedRelationTupleRow> =
+                builder.build_query_as().fetch_all(&self.pool).await?;
+
+            if rows.is_empty() {
+                break;
+            }
+
+            for row in rows.iter() {
+                let to = RelationTuple {
+                    namespace: row.subject_set_namespace.clone(),
+                    object: row.subject_set_object,
+                    relation: computed_relation.to_string(),
+                    subject: start.subject.clone(),
+                };
+                results.push(TraversalResult {
+                    from: start.clone(),
+                    to,
+                    via: Traversal::TupleToUserset,
+                    found: row.found,
+                });
+                shard_id = row.shard_id;
+            }
+
+            if rows.len() < QUERY_LIMIT as usize {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn traverse_subject_set_members(
+        &self,
+        ctx: &RequestContext,
+        subject_set: &SubjectSet,
+    ) -> HeimdallResult<Vec<Subject>> {
+        let span = info_span!("traverse_subject_set_members");
+        let _guard = span.enter();
+
+        let mut shard_id = Uuid::nil();
+        let mut results = Vec::new();
+
+        loop {
+            let mut builder = QueryBuilder::new(
+                r#"SELECT shard_id, nid, namespace, object, relation, subject_id,
+                          subject_set_namespace, subject_set_object, subject_set_relation, commit_time
+                   FROM heimdall_relation_tuples WHERE nid = "#,
+            );
+            builder.push_bind(ctx.network_id());
+            Self::with_snapshot(&mut builder, ctx, None);
+            builder.push(" AND namespace = ");
+            builder.push_bind(subject_set.namespace.clone());
+            builder.push(" AND object = ");
+            builder.push_bind(subject_set.object);
+            builder.push(" AND relation = ");
+            builder.push_bind(subject_set.relation.clone());
+            builder.push(" AND shard_id > ");
+            builder.push_bind(shard_id);
+            builder.push(" ORDER BY shard_id LIMIT ");
+            builder.push_bind(QUERY_LIMIT);
+
+            let rows: Vec<DbRelationTuple> = builder.build_query_as().fetch_all(&self.pool).await?;
+
+            let row_count = rows.len();
+            if let Some(last) = rows.last() {
+                shard_id = last.shard_id;
+            }
+            results.extend(rows.into_iter().map(|row| RelationTuple::from(row).subject));
+
+            if row_count < QUERY_LIMIT as usize {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn traverse_reverse(
+        &self,
+        ctx: &RequestContext,
+        subject: &Subject,
+    ) -> HeimdallResult<Vec<RelationTuple>> {
+        let span = info_span!("traverse_reverse");
+        let _guard = span.enter();
+
+        let mut shard_id = Uuid::nil();
+        let mut results = Vec::new();
+
+        loop {
+            let mut builder = QueryBuilder::new(
+                r#"SELECT shard_id, nid, namespace, object, relation, subject_id,
+                          subject_set_namespace, subject_set_object, subject_set_relation, commit_time
+                   FROM heimdall_relation_tuples WHERE nid = "#,
+            );
+            builder.push_bind(ctx.network_id());
+            Self::with_snapshot(&mut builder, ctx, None);
+            // Both branches match the predicates of the reverse subject indexes.
+            match subject {
+                Subject::Direct(SubjectID { id }) => {
+                    builder.push(" AND subject_id = ");
+                    builder.push_bind(*id);
+                    builder.push(" AND subject_set_namespace IS NULL AND subject_set_object IS NULL AND subject_set_relation IS NULL");
+                }
+                Subject::Set(SubjectSet {
+                    namespace, object, ..
+                }) => {
+                    builder.push(" AND subject_id IS NULL AND subject_set_namespace = ");
+                    builder.push_bind(namespace.clone());
+                    builder.push(" AND subject_set_object = ");
+                    builder.push_bind(*object);
+                }
+            }
+            builder.push(" AND shard_id > ");
+            builder.push_bind(shard_id);
+            builder.push(" ORDER BY shard_id LIMIT ");
+            builder.push_bind(QUERY_LIMIT);
+
+            let rows: Vec<DbRelationTuple> = builder.build_query_as().fetch_all(&self.pool).await?;
+
+            let row_count = rows.len();
+            if let Some(last) = rows.last() {
+                shard_id = last.shard_id;
+            }
+            results.extend(rows.into_iter().map(RelationTuple::from));
+
+            if row_count < QUERY_LIMIT as usize {
+                break;
+            }
+        }
+        Ok(results)
     }
 }
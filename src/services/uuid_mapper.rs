@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use tracing::{info_span, trace};
 use uuid::Uuid;
 
@@ -26,7 +26,7 @@ impl UuidMappingService {
 
     async fn batch_from_uuids(
         &self,
-        _ctx: &RequestContext,
+        ctx: &RequestContext,
         ids: &[Uuid],
         pagination_params: &TokenPagination,
     ) -> HeimdallResult<Vec<String>> {
@@ -44,17 +44,23 @@ impl UuidMappingService {
             id_idx.entry(*id).or_default().push(i)
         }
 
-        let mut results = vec![String::new(); ids.len()];
+        // IDs without a mapping were not created from a string and stand for
+        // themselves.
+        let mut results: Vec<String> = ids.iter().map(Uuid::to_string).collect();
 
         let keys: Vec<&Uuid> = id_idx.keys().collect();
 
         for id_chunk in keys.chunks(page_size) {
             let id_params = id_chunk.iter().copied().cloned().collect::<Vec<Uuid>>();
 
+            // Mappings written before they were tied to a network have no nid
+            // and are not returned until a write claims them, see
+            // `insert_uuids`.
             let uuid_mapping_result: Vec<UuidMapping> = sqlx::query_as(
-                "SELECT id, string_representation FROM heimdall_uuid_mappings WHERE id = ANY($1)",
+                "SELECT id, string_representation FROM heimdall_uuid_mappings WHERE nid = $2 AND id = ANY($1)",
             )
             .bind(id_params)
+            .bind(ctx.network_id())
             .fetch_all(&self.pool)
             .await?;
 
@@ -80,7 +86,33 @@ impl UuidMappingService {
         Ok(results)
     }
 
-    async fn insert_uuids(&self, values: &[UuidMapping]) -> HeimdallResult<()> {
+    /// Stores the mappings of a write inside its transaction, so they are
+    /// only kept when the write commits.
+    pub async fn insert_mappings(
+        conn: &mut PgConnection,
+        ctx: &RequestContext,
+        mappings: &[UuidMapping],
+    ) -> HeimdallResult<()> {
+        if mappings.is_empty() {
+            return Ok(());
+        }
+
+        let span = info_span!("insert_mappings", mappings_length = mappings.len());
+        let _guard = span.enter();
+
+        for chunk in mappings.chunks(CHUNK_SIZE_INSERT_UUID_MAPPINGS) {
+            trace!(count = chunk.len(), "adding UUID mappings");
+            Self::insert_uuids(&mut *conn, ctx, chunk).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn insert_uuids(
+        conn: &mut PgConnection,
+        ctx: &RequestContext,
+        values: &[UuidMapping],
+    ) -> HeimdallResult<()> {
         let mut ids = Vec::with_capacity(values.len());
         let mut string_reps = Vec::with_capacity(values.len());
         for value in values {
@@ -88,11 +120,13 @@ impl UuidMappingService {
             string_reps.push(value.string_representation.clone());
         }
         sqlx::query(
-            "INSERT INTO heimdall_uuid_mappings (id, string_representation) SELECT * FROM UNNEST($1::UUID[], $2::VARCHAR[]) ON CONFLICT (id) DO NOTHING"
+            "INSERT INTO heimdall_uuid_mappings (id, string_representation, nid) SELECT *, $3 FROM UNNEST($1::UUID[], $2::VARCHAR[])
+             ON CONFLICT (id) DO UPDATE SET nid = EXCLUDED.nid WHERE heimdall_uuid_mappings.nid IS NULL"
         )
             .bind(ids)
             .bind(string_reps)
-            .execute(&self.pool)
+            .bind(ctx.network_id())
+            .execute(conn)
             .await?;
 
         Ok(())
@@ -101,43 +135,6 @@ impl UuidMappingService {
 
 #[async_trait]
 impl UuidMappingManager for UuidMappingService {
-    async fn map_strings_to_uuids(
-        &self,
-        ctx: &RequestContext,
-        values: &[String],
-    ) -> HeimdallResult<Vec<Uuid>> {
-        if values.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let span = info_span!("map_strings_to_uuids");
-        let _guard = span.enter();
-
-        let ids = self.map_strings_to_uuids_readonly(ctx, values).await?;
-
-        trace!(values = ?values, ids = ?ids, "adding UUID mappings");
-
-        let mut mappings = Vec::with_capacity(values.len());
-
-        for (string_representation, id) in values.iter().zip(ids.iter()) {
-            mappings.push(UuidMapping {
-                id: *id,
-                string_representation: string_representation.clone(),
-            });
-        }
-
-        mappings.sort_by(|a, b| a.id.cmp(&b.id));
-        mappings.dedup_by(|a, b| a.id.eq(&b.id));
-
-        span.record("mappings_length", mappings.len());
-
-        for mapping in mappings.chunks(CHUNK_SIZE_INSERT_UUID_MAPPINGS) {
-            self.insert_uuids(mapping).await?;
-        }
-
-        Ok(ids)
-    }
-
     async fn map_strings_to_uuids_readonly(
         &self,
         ctx: &RequestContext,
@@ -0,0 +1,254 @@
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use config::{Config, Environment, File};
+use serde::Deserialize;
+use sqlx::{PgPool, postgres::PgPoolOptions};
+use uuid::Uuid;
+
+use crate::{
+    engines::{
+        check::{DEFAULT_MAX_BATCH_SIZE, DEFAULT_MAX_DEPTH},
+        watch::DEFAULT_POLL_INTERVAL_MS,
+    },
+    error::{HeimdallError, HeimdallResult},
+    models::namespace::Namespace,
+    services::namespace::NamespaceService,
+};
+
+pub const DEFAULT_CONFIG_FILE: &str = "config/heimdall.yml";
+
+const ENV_PREFIX: &str = "HEIMDALL";
+
+/// Server configuration. Values are read from a YAML, TOML or JSON5 file and
+/// then overridden by `HEIMDALL_*` environment variables, with `__` separating
+/// nested keys, e.g. `HEIMDALL_SERVE__READ__HTTP__PORT=8080`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub dsn: String,
+    pub database: DatabaseSettings,
+    pub serve: ServeSettings,
+    pub log: LogSettings,
+    pub limit: LimitSettings,
+    pub watch: WatchSettings,
+    pub network: NetworkSettings,
+    pub auth: AuthSettings,
+    pub namespaces: Vec<Namespace>,
+    /// Reads namespaces from a separate file instead of `namespaces`.
+    pub namespaces_file: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct DatabaseSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout_secs: u64,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+        }
+    }
+}
+
+/// The read API (listing, check, expand and watch) and the write API (writing
+/// relation tuples, networks and API keys) each get their own HTTP and gRPC
+/// listeners, so they can be exposed to different callers.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ServeSettings {
+    pub read: ApiListenSettings,
+    pub write: ApiListenSettings,
+    /// How long in-flight requests may take to finish after a shutdown signal.
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for ServeSettings {
+    fn default() -> Self {
+        Self {
+            read: ApiListenSettings::new(4466, 4467),
+            write: ApiListenSettings::new(4468, 4469),
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiListenSettings {
+    pub http: ListenSettings,
+    pub grpc: ListenSettings,
+}
+
+impl ApiListenSettings {
+    fn new(http_port: u16, grpc_port: u16) -> Self {
+        Self {
+            http: ListenSettings {
+                host: "0.0.0.0".to_string(),
+                port: http_port,
+            },
+            grpc: ListenSettings {
+                host: "0.0.0.0".to_string(),
+                port: grpc_port,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListenSettings {
+    pub host: String,
+    pub port: u16,
+}
+
+impl ListenSettings {
+    pub fn address(&self) -> HeimdallResult<SocketAddr> {
+        format!("{}:{}", self.host, self.port).parse().map_err(|_| {
+            HeimdallError::InvalidSettings(format!(
+                "invalid listen address {}:{}",
+                self.host, self.port
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    /// Default filter when `RUST_LOG` is not set, e.g. `info` or `heimdall=debug`.
+    pub level: String,
+    pub format: LogFormat,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// How requests are attributed to a network when they do not name one in the
+/// `x-heimdall-network-id` header.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Maps a request's host, without the port, to its network.
+    pub hosts: HashMap<String, Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AuthSettings {
+    /// Requires a bearer API key on every request. Only disable this when the
+    /// ports are not reachable by untrusted callers.
+    pub enabled: bool,
+    /// Hex-encoded SHA-256 hashes of root keys, which may act on any network
+    /// and manage networks. Generate one with `heimdall api-key generate`.
+    pub root_key_hashes: Vec<String>,
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            root_key_hashes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LimitSettings {
+    pub max_depth: u32,
+    /// Maximum number of checks in one batch check request.
+    pub max_batch_size: usize,
+}
+
+impl Default for LimitSettings {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct WatchSettings {
+    /// How often a watch stream looks for new changes once it has caught up.
+    pub poll_interval_ms: u64,
+}
+
+impl Default for WatchSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: DEFAULT_POLL_INTERVAL_MS,
+        }
+    }
+}
+
+impl WatchSettings {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+}
+
+impl Settings {
+    /// Loads `path`, or the default config file when it exists.
+    pub fn load(path: Option<&str>) -> HeimdallResult<Self> {
+        let file = match path {
+            Some(path) => File::with_name(path),
+            None => File::with_name(DEFAULT_CONFIG_FILE).required(false),
+        };
+
+        let settings: Settings = Config::builder()
+            .add_source(file)
+            .add_source(
+                Environment::with_prefix(ENV_PREFIX)
+                    .prefix_separator("_")
+                    .separator("__")
+                    .try_parsing(true),
+            )
+            .build()?
+            .try_deserialize()?;
+
+        if settings.dsn.is_empty() {
+            return Err(HeimdallError::InvalidSettings(
+                "dsn is required".to_string(),
+            ));
+        }
+
+        Ok(settings)
+    }
+
+    pub async fn connect(&self) -> HeimdallResult<PgPool> {
+        let pool = PgPoolOptions::new()
+            .max_connections(self.database.max_connections)
+            .min_connections(self.database.min_connections)
+            .acquire_timeout(Duration::from_secs(self.database.acquire_timeout_secs))
+            .connect(&self.dsn)
+            .await?;
+        Ok(pool)
+    }
+
+    pub fn namespace_service(&self) -> HeimdallResult<NamespaceService> {
+        match self.namespaces_file {
+            Some(ref path) => NamespaceService::from_config_file(path),
+            None => NamespaceService::new(self.namespaces.clone()),
+        }
+    }
+}